@@ -3,12 +3,16 @@ use std::{collections::HashSet, sync::mpsc};
 use ratatui::Frame;
 
 use crate::{
+    color,
     component::Component,
     config::Config,
-    db::file_db::FileDB,
-    layers::spawn_layer_load,
+    db::{db_connection::DBConnection, file_db::FileDB},
+    error::{ErrorReport, SessionError, StateError},
+    export::spawn_export,
+    layers::{builtin, spawn_layer_load},
     message::Message,
-    model::{InteractionMode, LayerState, Model},
+    model::{InspectingLocationView, InteractionMode, LayerState, Model},
+    state::{SavedState, SessionView},
     update::Update,
     view::View,
 };
@@ -21,9 +25,14 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(db: FileDB, config: Config, async_tx: mpsc::Sender<Update>) -> Self {
+    pub fn new(
+        db: FileDB,
+        config: Config,
+        saved_state: SavedState,
+        async_tx: mpsc::Sender<Update>,
+    ) -> Self {
         Self {
-            model: Model::new(config),
+            model: Model::new(config, saved_state),
             view: View::new(&db),
             db,
             async_tx,
@@ -31,24 +40,185 @@ impl App {
     }
 
     pub fn handle(&mut self, msg: Message) {
-        self.model.err = None;
+        // Moving the mouse over the map shouldn't dismiss an error.
+        if !matches!(msg, Message::Mouse { .. }) {
+            self.model.err = None;
+        }
         let mut queue: Vec<Message> = vec![msg];
         while let Some(m) = queue.pop() {
             if let Message::LoadLayers = m {
                 self.spawn_all_layer_loads();
                 continue;
             }
-            let (updates, follow_ups) = self.view.update(&m, &self.model, &self.db);
-            for u in updates {
-                self.model.apply(u);
+            if let Message::RestoreSession = m {
+                queue.extend(self.restore_session());
+                continue;
+            }
+            if let Message::Export { path, job } = m {
+                spawn_export(path, job, self.async_tx.clone());
+                continue;
+            }
+            if let Message::Reload = m {
+                self.reload();
+                continue;
             }
+            let (updates, follow_ups) = self.view.update(&m, &self.model, &self.db);
+            self.apply_updates(updates);
             queue.extend(follow_ups);
         }
     }
 
+    fn apply_updates(&mut self, updates: Vec<Update>) {
+        for u in updates {
+            let persist = u.touches_saved_state();
+            self.model.apply(u);
+            if persist {
+                self.save_state();
+            }
+        }
+    }
+
     pub fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         self.view.render(frame, area, &self.model);
+        color::quantize_buffer(frame.buffer_mut(), self.model.config.ui.color_depth);
+    }
+
+    /// Record where the user is and write the state file. Called on exit,
+    /// after the terminal is gone, so failures are returned rather than shown.
+    /// Quitting from the browse list before resuming keeps the session that
+    /// could have been resumed.
+    pub fn save_session(&mut self) -> Result<(), StateError> {
+        self.model.saved_state.session = self
+            .view
+            .session(&self.model)
+            .or(self.model.restorable_session.take());
+        self.model.saved_state.save()
+    }
+
+    /// Open the pending session. Ids that no longer exist (or were mistyped on
+    /// the command line) are reported and skipped rather than applied.
+    /// Returns the screen's follow-up messages from activating.
+    fn restore_session(&mut self) -> Vec<Message> {
+        let Some(mut session) = self.model.restorable_session.take() else {
+            return vec![];
+        };
+        let location = match self.db.get_by_id(&session.location_id) {
+            Ok(location) => location,
+            Err(e) => {
+                self.model.err = Some(ErrorReport::new(&e));
+                return vec![];
+            }
+        };
+        let mut unknown = Vec::new();
+        if let Some(layer_id) = &session.active_layer {
+            if !self.model.config.layers.iter().any(|l| &l.id == layer_id) {
+                unknown.push(SessionError::UnknownLayer(layer_id.clone()));
+                session.active_layer = None;
+            }
+        }
+        if let Some(region_id) = &session.selected_region {
+            if !location.regions.iter().any(|r| &r.metadata.id == region_id) {
+                unknown.push(SessionError::UnknownRegion {
+                    region: region_id.clone(),
+                    location: location.tag.id.clone(),
+                });
+                session.selected_region = None;
+            }
+        }
+
+        self.model.apply(Update::SetLocation(location));
+        if session.view == SessionView::Inspect {
+            self.model.apply(Update::SetInspectingLocationView(
+                InspectingLocationView::InspectScreen,
+            ));
+        }
+        if let Some(layer_id) = &session.active_layer {
+            self.model.apply(Update::SetActiveLayer {
+                layer_id: layer_id.clone(),
+            });
+        }
+        self.save_state();
+
+        // Let the screen fit itself first, then lay the saved viewport over it.
+        let (updates, follow_ups) = self.view.update(&Message::Activated, &self.model, &self.db);
+        self.apply_updates(updates);
+        if let InteractionMode::InspectingLocation { state } = &self.model.interaction_mode {
+            self.view.restore(&session, &state.location);
+        }
+        if session.view == SessionView::Inspect {
+            self.spawn_all_layer_loads();
+        }
+        if let Some(e) = unknown.first() {
+            self.model.err = Some(ErrorReport::new(e));
+        }
+        follow_ups
+    }
+
+    /// Re-read the config and dataset after a change on disk, keeping the
+    /// open location, screen and selection. On failure the previous config
+    /// and dataset stay in use.
+    fn reload(&mut self) {
+        let loaded = self
+            .model
+            .config
+            .reload()
+            .map_err(|e| ErrorReport::new(&e))
+            .and_then(|config| {
+                let db = FileDB::new(&config.data.root_dir.join("locations.json"))
+                    .map_err(|e| ErrorReport::new(&e))?;
+                Ok((config, db))
+            });
+        let (config, db) = match loaded {
+            Ok(loaded) => loaded,
+            Err(report) => {
+                self.model.apply(Update::ToastError(format!(
+                    "Reload failed: {}",
+                    report.one_line()
+                )));
+                return;
+            }
+        };
+        self.model.config = config;
+        self.db = db;
+        self.view
+            .location_select_screen
+            .reload(&self.db, &self.model);
+
+        if let InteractionMode::InspectingLocation { state } = &self.model.interaction_mode {
+            let location_id = state.location.tag.id.clone();
+            let on_inspect = matches!(state.view, InspectingLocationView::InspectScreen);
+            let selected_region = self.view.inspect_screen.selected_region_id(&state.location);
+            match self.db.get_by_id(&location_id) {
+                Ok(location) => {
+                    if let Some(region_id) = &selected_region {
+                        self.view
+                            .inspect_screen
+                            .select_region_by_id(&location, region_id);
+                    }
+                    self.model.apply(Update::ReloadLocation(location));
+                    if on_inspect {
+                        self.spawn_all_layer_loads();
+                    }
+                }
+                Err(e) => {
+                    self.model.apply(Update::ClearLocation);
+                    self.model.apply(Update::ToastError(format!(
+                        "Reloaded, but {}",
+                        ErrorReport::new(&e).one_line()
+                    )));
+                    return;
+                }
+            }
+        }
+        self.model
+            .apply(Update::Toast("Reloaded config and dataset".to_string()));
+    }
+
+    fn save_state(&mut self) {
+        if let Err(e) = self.model.saved_state.save() {
+            self.model.err = Some(ErrorReport::new(&e));
+        }
     }
 
     fn spawn_all_layer_loads(&mut self) {
@@ -70,11 +240,29 @@ impl App {
             .filter(|(_, s)| {
                 matches!(
                     s,
-                    LayerState::Loading | LayerState::Loaded(_) | LayerState::Failed(_)
+                    LayerState::Loading
+                        | LayerState::Loaded(_)
+                        | LayerState::LoadedPois(_)
+                        | LayerState::LoadedLines(_)
+                        | LayerState::Failed(_)
                 )
             })
             .map(|(id, _)| id.clone())
             .collect();
+        // Built-in layers are quick to work out from the regions, so they're
+        // done here rather than on a thread. Those averaging another layer
+        // are worked out by the model once that layer's values arrive.
+        let builtin_values: Vec<Update> = layers
+            .iter()
+            .filter(|l| !already_triggered.contains(&l.id) && l.source.is_none())
+            .filter_map(|l| {
+                Some(Update::SetLayerData {
+                    location_id: location_id.clone(),
+                    layer_id: l.id.clone(),
+                    layer_data: builtin::compute(l.builtin?, &state.location, None),
+                })
+            })
+            .collect();
         for layer_config in layers {
             if already_triggered.contains(&layer_config.id) {
                 continue;
@@ -83,6 +271,9 @@ impl App {
                 location_id: location_id.clone(),
                 layer_id: layer_config.id.clone(),
             });
+            if layer_config.builtin.is_some() {
+                continue;
+            }
             spawn_layer_load(
                 layer_config,
                 location_id.clone(),
@@ -91,5 +282,8 @@ impl App {
                 self.async_tx.clone(),
             );
         }
+        for update in builtin_values {
+            self.model.apply(update);
+        }
     }
 }
@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    export::regions::RegionFormat,
+    state::{Session, SessionView, Viewport},
+};
+
+#[derive(Parser)]
+#[command(
+    name = "loctui",
+    version,
+    about = "Inspect geographic locations in the terminal"
+)]
+pub struct Cli {
+    /// Run a non-interactive command instead of launching the TUI
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub session: SessionArgs,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+impl Cli {
+    /// Session flags only make sense when launching the TUI. Checked by hand
+    /// because clap's `args_conflicts_with_subcommands` would also reject the
+    /// global config flags.
+    pub fn parse_checked() -> Self {
+        let cli = Self::parse();
+        if cli.command.is_some() && cli.session.location.is_some() {
+            Self::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--location can't be used with a subcommand",
+                )
+                .exit();
+        }
+        cli
+    }
+}
+
+/// Where to load config from. Accepted before or after a subcommand.
+#[derive(Args, Clone, Default)]
+pub struct ConfigArgs {
+    /// Config file; repeat to layer several, later files overriding earlier
+    /// ones. Replaces LOCTUI_CONFIG and ~/.config/loctui/config.toml
+    #[arg(long = "config", global = true, value_name = "FILE")]
+    pub configs: Vec<PathBuf>,
+
+    /// Apply the config's [profile.NAME] section on top
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Dataset directory, overriding data.root_dir
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_root: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// List locations, optionally fuzzy-filtered like the location search
+    List {
+        /// Fuzzy search query
+        query: Option<String>,
+        /// Only starred locations
+        #[arg(long, conflicts_with = "recent")]
+        starred: bool,
+        /// Only recently viewed locations, most recent first
+        #[arg(long)]
+        recent: bool,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Print a location's metadata and its regions
+    Show {
+        id: String,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Work with configured layers
+    Layer {
+        #[command(subcommand)]
+        command: LayerCommand,
+    },
+    /// Write a location's regions joined with layer values to a file
+    Export {
+        location: String,
+        /// Layer id to include; repeat for more. Defaults to every `values` layer
+        #[arg(long = "layer")]
+        layers: Vec<String>,
+        /// Defaults to the output file's extension
+        #[arg(long, value_enum)]
+        format: Option<RegionFormat>,
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+    /// Check that every location in the dataset loads
+    Validate {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Render a screen headlessly and write it out. Without `--location`
+    /// this renders the location list; `svg` and `png` need a location.
+    Render {
+        #[command(flatten)]
+        session: SessionArgs,
+        /// Width in terminal cells
+        #[arg(long, default_value_t = 120)]
+        width: u16,
+        /// Height in terminal cells
+        #[arg(long, default_value_t = 40)]
+        height: u16,
+        #[arg(long, value_enum, default_value_t = SnapshotFormat::Text)]
+        format: SnapshotFormat,
+        /// File to write to; stdout if omitted
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LayerCommand {
+    /// Run one layer command for a location and print its values, POIs or lines
+    Run {
+        layer: String,
+        location: String,
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Table,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Plain characters, no styling
+    Text,
+    /// Characters with ANSI colour escape sequences
+    Ansi,
+    /// A standalone HTML page with inline styles
+    Html,
+    /// The open location's map as vector graphics
+    Svg,
+    /// The open location's map as a raster image
+    Png,
+}
+
+/// Startup state, mirroring what is saved on exit. Passing `--location` skips
+/// the restore prompt and opens straight into that location.
+#[derive(Args)]
+pub struct SessionArgs {
+    /// Location id to open on startup
+    #[arg(long)]
+    pub location: Option<String>,
+
+    /// Screen to open the location on
+    #[arg(long, value_enum, requires = "location", default_value = "summary")]
+    pub view: SessionView,
+
+    /// Layer id to make active
+    #[arg(long, requires = "location")]
+    pub layer: Option<String>,
+
+    /// Region id to select on the inspect screen
+    #[arg(long, requires = "location")]
+    pub region: Option<String>,
+
+    /// Map pan offset, in the map's own units
+    #[arg(long, requires = "location", allow_hyphen_values = true)]
+    pub offset_x: Option<f64>,
+
+    /// Map pan offset, in the map's own units
+    #[arg(long, requires = "location", allow_hyphen_values = true)]
+    pub offset_y: Option<f64>,
+
+    /// Map scale (smaller is more zoomed in)
+    #[arg(long, requires = "location")]
+    pub scale: Option<f64>,
+}
+
+impl SessionArgs {
+    pub fn to_session(&self) -> Option<Session> {
+        let location_id = self.location.clone()?;
+        let viewport = (self.offset_x.is_some() || self.offset_y.is_some() || self.scale.is_some())
+            .then(|| Viewport {
+                offset_x: self.offset_x.unwrap_or(0.0),
+                offset_y: self.offset_y.unwrap_or(0.0),
+                scale: self.scale,
+            });
+        let (summary_map, inspect_map) = match self.view {
+            SessionView::Summary => (viewport, None),
+            SessionView::Inspect => (None, viewport),
+        };
+        Some(Session {
+            location_id,
+            view: self.view,
+            active_layer: self.layer.clone(),
+            selected_region: self.region.clone(),
+            browse_map: None,
+            summary_map,
+            inspect_map,
+        })
+    }
+}
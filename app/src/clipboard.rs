@@ -0,0 +1,65 @@
+use std::io::Write;
+
+use arboard::Clipboard;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::error::ClipboardError;
+
+/// Which clipboard `copy` may use, set by `ui.clipboard` in the config.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardMethod {
+    /// The native clipboard, falling back to OSC 52 when there is none
+    /// (SSH sessions, containers without X or Wayland).
+    #[default]
+    Auto,
+    Native,
+    Osc52,
+}
+
+/// How a copy actually reached the clipboard.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CopiedVia {
+    Native,
+    Osc52,
+}
+
+impl CopiedVia {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CopiedVia::Native => "system clipboard",
+            CopiedVia::Osc52 => "OSC 52",
+        }
+    }
+}
+
+pub fn copy(text: &str, method: ClipboardMethod) -> Result<CopiedVia, ClipboardError> {
+    match method {
+        ClipboardMethod::Native => copy_native(text).map(|_| CopiedVia::Native),
+        ClipboardMethod::Osc52 => copy_osc52(text).map(|_| CopiedVia::Osc52),
+        ClipboardMethod::Auto => copy_native(text)
+            .map(|_| CopiedVia::Native)
+            .or_else(|_| copy_osc52(text).map(|_| CopiedVia::Osc52)),
+    }
+}
+
+fn copy_native(text: &str) -> Result<(), ClipboardError> {
+    Clipboard::new()
+        .and_then(|mut cb| cb.set_text(text))
+        .map_err(ClipboardError::Native)
+}
+
+/// Ask the terminal to set its clipboard. Works over SSH as long as the
+/// terminal honours OSC 52; there is no reply, so success can't be confirmed.
+fn copy_osc52(text: &str) -> Result<(), ClipboardError> {
+    let mut seq = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    // tmux only forwards escape sequences wrapped in its passthrough DCS.
+    if std::env::var_os("TMUX").is_some() {
+        seq = format!("\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b"));
+    }
+    let mut stdout = std::io::stdout();
+    stdout
+        .write_all(seq.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(ClipboardError::Osc52)
+}
@@ -3,13 +3,16 @@ use std::sync::OnceLock;
 use geo::LineString;
 use geojson::{GeoJson, Value};
 
-use crate::domain::geometry::{Polyline, WGS84};
+use crate::domain::{
+    geometry::{Polyline, WGS84},
+    simplify::LineLevels,
+};
 
 const RAW: &str = include_str!("assets/coastlines.json");
 
-pub fn coastlines() -> &'static [Polyline<WGS84>] {
-    static CACHE: OnceLock<Vec<Polyline<WGS84>>> = OnceLock::new();
-    CACHE.get_or_init(parse).as_slice()
+pub fn coastlines() -> &'static LineLevels<WGS84> {
+    static CACHE: OnceLock<LineLevels<WGS84>> = OnceLock::new();
+    CACHE.get_or_init(|| LineLevels::new(parse()))
 }
 
 fn parse() -> Vec<Polyline<WGS84>> {
@@ -0,0 +1,193 @@
+use ratatui::{buffer::Buffer, style::Color};
+
+/// How many colours the terminal can show, set by `ui.color_depth`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// Detected from `NO_COLOR`, `COLORTERM` and `TERM`.
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+    /// No colour at all: text attributes only.
+    #[serde(rename = "none")]
+    None,
+}
+
+impl ColorDepth {
+    /// `Auto` replaced by what the environment says. A set `NO_COLOR` wins;
+    /// an explicit depth in the config wins over that.
+    pub fn resolve(self) -> Self {
+        if self != ColorDepth::Auto {
+            return self;
+        }
+        let env = |name| std::env::var(name).unwrap_or_default();
+        if !env("NO_COLOR").is_empty() {
+            return ColorDepth::None;
+        }
+        let term = env("TERM");
+        if term == "dumb" {
+            return ColorDepth::None;
+        }
+        if matches!(env("COLORTERM").as_str(), "truecolor" | "24bit") {
+            return ColorDepth::TrueColor;
+        }
+        if term.contains("256color") {
+            return ColorDepth::Ansi256;
+        }
+        ColorDepth::Ansi16
+    }
+
+    /// Whether region fills need patterns to stay readable: with 16 colours
+    /// or fewer a colour ramp collapses into a handful of steps.
+    pub fn is_limited(self) -> bool {
+        matches!(self.resolve(), ColorDepth::Ansi16 | ColorDepth::None)
+    }
+}
+
+/// Rewrite every cell's colours to what `depth` can show, after the whole
+/// frame is drawn, so widgets can keep using RGB freely.
+pub fn quantize_buffer(buffer: &mut Buffer, depth: ColorDepth) {
+    let depth = depth.resolve();
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+    for cell in buffer.content.iter_mut() {
+        cell.fg = quantize(cell.fg, depth);
+        cell.bg = quantize(cell.bg, depth);
+    }
+}
+
+pub fn quantize(color: Color, depth: ColorDepth) -> Color {
+    match (depth, color) {
+        (_, Color::Reset) | (ColorDepth::Auto | ColorDepth::TrueColor, _) => color,
+        (ColorDepth::None, _) => Color::Reset,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+        (ColorDepth::Ansi256, _) => color,
+        (ColorDepth::Ansi16, Color::Rgb(..) | Color::Indexed(16..)) => {
+            rgb(color).map(nearest_16).unwrap_or(color)
+        }
+        (ColorDepth::Ansi16, _) => color,
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> i32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Closest entry of the xterm 6x6x6 cube or grey ramp. The first 16 are
+/// skipped: terminals often remap them.
+fn nearest_256(c: (u8, u8, u8)) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |v: u8| {
+        (0..6)
+            .min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let (ri, gi, bi) = (level(c.0), level(c.1), level(c.2));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+    let cube_rgb = (
+        LEVELS[ri as usize],
+        LEVELS[gi as usize],
+        LEVELS[bi as usize],
+    );
+
+    let avg = (c.0 as u16 + c.1 as u16 + c.2 as u16) / 3;
+    let grey_i = ((avg.saturating_sub(8)) / 10).min(23) as u8;
+    let grey = 8 + grey_i * 10;
+    if distance(c, (grey, grey, grey)) < distance(c, cube_rgb) {
+        232 + grey_i
+    } else {
+        cube
+    }
+}
+
+fn nearest_16(c: (u8, u8, u8)) -> Color {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    NAMED
+        .into_iter()
+        .min_by_key(|&n| rgb(n).map_or(i32::MAX, |n| distance(c, n)))
+        .unwrap_or(Color::Reset)
+}
+
+pub fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Approximate RGB for a terminal colour (xterm defaults for the named and
+/// indexed palettes). `None` for `Reset`, i.e. the surrounding default.
+pub fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const NAMED: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+    let indexed = |i: u8| match i {
+        0..=15 => NAMED[i as usize],
+        16..=231 => {
+            let i = i - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    };
+    Some(match color {
+        Color::Reset => return None,
+        Color::Black => NAMED[0],
+        Color::Red => NAMED[1],
+        Color::Green => NAMED[2],
+        Color::Yellow => NAMED[3],
+        Color::Blue => NAMED[4],
+        Color::Magenta => NAMED[5],
+        Color::Cyan => NAMED[6],
+        Color::Gray => NAMED[7],
+        Color::DarkGray => NAMED[8],
+        Color::LightRed => NAMED[9],
+        Color::LightGreen => NAMED[10],
+        Color::LightYellow => NAMED[11],
+        Color::LightBlue => NAMED[12],
+        Color::LightMagenta => NAMED[13],
+        Color::LightCyan => NAMED[14],
+        Color::White => NAMED[15],
+        Color::Indexed(i) => indexed(i),
+        Color::Rgb(r, g, b) => (r, g, b),
+    })
+}
@@ -0,0 +1,47 @@
+pub mod export;
+pub mod layer;
+pub mod list;
+pub mod render;
+pub mod show;
+pub mod table;
+pub mod validate;
+
+use std::error::Error;
+
+use crate::{cli::Command, cli::LayerCommand, config::Config, db::file_db::FileDB};
+
+/// Run a non-interactive subcommand, printing to stdout.
+pub fn run(command: Command, config: Config) -> Result<(), Box<dyn Error>> {
+    let db = FileDB::new(&config.data.root_dir.join("locations.json"))?;
+    match command {
+        Command::List {
+            query,
+            starred,
+            recent,
+            format,
+        } => list::run(&db, query, starred, recent, format),
+        Command::Show { id, format } => show::run(&db, &id, format),
+        Command::Layer {
+            command:
+                LayerCommand::Run {
+                    layer,
+                    location,
+                    format,
+                },
+        } => layer::run(&db, &config, &layer, &location, format),
+        Command::Export {
+            location,
+            layers,
+            format,
+            output,
+        } => export::run(&db, &config, &location, &layers, format, &output),
+        Command::Validate { format } => validate::run(&db, format),
+        Command::Render {
+            session,
+            width,
+            height,
+            format,
+            output,
+        } => render::run(db, config, &session, width, height, format, output),
+    }
+}
@@ -0,0 +1,67 @@
+use std::{error::Error, path::Path};
+
+use crate::{
+    config::{Config, LayerConfig, LayerKind},
+    db::{db_connection::DBConnection, file_db::FileDB},
+    error::ErrorReport,
+    export::regions::{write_regions, LayerColumn, RegionFormat},
+    layers::layer_values,
+};
+
+pub fn run(
+    db: &FileDB,
+    config: &Config,
+    location_id: &String,
+    layer_ids: &[String],
+    format: Option<RegionFormat>,
+    output: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let format = format
+        .or_else(|| RegionFormat::from_path(output))
+        .ok_or("Cannot tell the format from the output file name; pass --format")?;
+    let layer_configs: Vec<&LayerConfig> = if layer_ids.is_empty() {
+        config
+            .layers
+            .iter()
+            .filter(|l| l.kind == LayerKind::Values)
+            .collect()
+    } else {
+        layer_ids
+            .iter()
+            .map(|id| match config.layers.iter().find(|l| &l.id == id) {
+                Some(l) if l.kind == LayerKind::Pois => {
+                    Err(format!("Layer '{}' gives POIs, not region values", id))
+                }
+                Some(l) if l.kind == LayerKind::Lines => {
+                    Err(format!("Layer '{}' gives lines, not region values", id))
+                }
+                Some(l) => Ok(l),
+                None => Err(format!("No layer '{}' in config", id)),
+            })
+            .collect::<Result<_, _>>()?
+    };
+    let location = db.get_by_id(location_id)?;
+
+    // A failing layer is left out rather than losing the rest of the export.
+    let mut loaded = vec![];
+    for layer_config in layer_configs {
+        match layer_values(
+            layer_config,
+            &config.layers,
+            &location,
+            &config.data.root_dir,
+        ) {
+            Ok(values) => loaded.push((layer_config.id.as_str(), values)),
+            Err(e) => eprintln!(
+                "warning: skipping layer '{}': {}",
+                layer_config.id,
+                ErrorReport::new(&e).one_line()
+            ),
+        }
+    }
+    let columns: Vec<LayerColumn> = loaded
+        .iter()
+        .map(|(id, values)| LayerColumn { id, values })
+        .collect();
+    write_regions(output, &location, &columns, format)
+}
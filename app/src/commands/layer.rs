@@ -0,0 +1,102 @@
+use std::error::Error;
+
+use crate::{
+    cli::Format,
+    commands::{
+        show::{print_line_table, print_poi_table},
+        table::print_table,
+    },
+    config::{Config, LayerKind},
+    db::{db_connection::DBConnection, file_db::FileDB},
+    domain::{lines::LineCollection, poi::PoiCollection},
+    layers::{layer_values, run_lines_command, run_poi_command},
+};
+
+pub fn run(
+    db: &FileDB,
+    config: &Config,
+    layer_id: &str,
+    location_id: &String,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let layer_config = config
+        .layers
+        .iter()
+        .find(|l| l.id == layer_id)
+        .ok_or_else(|| format!("No layer '{}' in config", layer_id))?;
+    let location = db.get_by_id(location_id)?;
+    let region_ids: Vec<String> = location
+        .regions
+        .iter()
+        .map(|p| p.metadata.id.clone())
+        .collect();
+    if layer_config.kind == LayerKind::Pois {
+        let collections = run_poi_command(
+            layer_config,
+            location_id,
+            &region_ids,
+            &config.data.root_dir,
+        )?;
+        match format {
+            Format::Json => {
+                let out: Vec<serde_json::Value> =
+                    collections.iter().map(PoiCollection::to_json).collect();
+                println!("{}", serde_json::to_string_pretty(&out)?);
+            }
+            Format::Table => print_poi_table(&collections),
+        }
+        return Ok(());
+    }
+    if layer_config.kind == LayerKind::Lines {
+        let collections = run_lines_command(
+            layer_config,
+            location_id,
+            &region_ids,
+            &config.data.root_dir,
+        )?;
+        match format {
+            Format::Json => {
+                let out: Vec<serde_json::Value> =
+                    collections.iter().map(LineCollection::to_json).collect();
+                println!("{}", serde_json::to_string_pretty(&out)?);
+            }
+            Format::Table => print_line_table(&collections),
+        }
+        return Ok(());
+    }
+    let values = layer_values(
+        layer_config,
+        &config.layers,
+        &location,
+        &config.data.root_dir,
+    )?;
+
+    match format {
+        Format::Json => {
+            // serde_json's map is ordered by key, so output is stable run to run.
+            let out: serde_json::Map<String, serde_json::Value> = region_ids
+                .iter()
+                .filter_map(|id| values.get(id).map(|v| (id.clone(), serde_json::json!(v))))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        Format::Table => {
+            let rows: Vec<Vec<String>> = location
+                .regions
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.metadata.id.clone(),
+                        p.metadata.name.clone(),
+                        values
+                            .get(&p.metadata.id)
+                            .map(|v| format!("{:.2}", v))
+                            .unwrap_or_else(|| "-".to_string()),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "NAME", &layer_config.name.to_uppercase()], &rows);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+use crate::{
+    cli::Format,
+    commands::table::print_table,
+    db::{db_connection::DBConnection, file_db::FileDB},
+    domain::location::LocationTag,
+    screens::location_select_screen::recompute_matches,
+    state::SavedState,
+};
+
+pub fn run(
+    db: &FileDB,
+    query: Option<String>,
+    starred: bool,
+    recent: bool,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let tags = db.get_tags();
+    let matches = recompute_matches(&query, &tags, &SkimMatcherV2::default());
+    let saved_state = SavedState::load();
+    let visible: Vec<&LocationTag> = if recent {
+        saved_state
+            .recent
+            .iter()
+            .filter_map(|id| matches.iter().map(|&i| &tags[i]).find(|t| &t.id == id))
+            .collect()
+    } else {
+        matches
+            .iter()
+            .map(|&i| &tags[i])
+            .filter(|t| !starred || saved_state.is_starred(&t.id))
+            .collect()
+    };
+
+    match format {
+        Format::Json => {
+            let out: Vec<serde_json::Value> = visible
+                .iter()
+                .map(|t| t.to_json(saved_state.is_starred(&t.id)))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        Format::Table => {
+            let rows: Vec<Vec<String>> = visible
+                .iter()
+                .map(|t| {
+                    vec![
+                        if saved_state.is_starred(&t.id) {
+                            "★"
+                        } else {
+                            ""
+                        }
+                        .to_string(),
+                        t.id.clone(),
+                        t.name.clone(),
+                        format!("{}/{}", t.country_code, t.country_subdivision),
+                        t.kind.clone(),
+                        t.status.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["", "ID", "NAME", "COUNTRY", "TYPE", "STATUS"], &rows);
+        }
+    }
+    Ok(())
+}
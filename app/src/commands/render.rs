@@ -0,0 +1,42 @@
+use std::{error::Error, io::Write, path::PathBuf};
+
+use crate::{
+    cli::{SessionArgs, SnapshotFormat},
+    config::Config,
+    db::file_db::FileDB,
+    export::MapFormat,
+    snapshot::{headless_app, render_buffer, to_ansi, to_html, to_text},
+    state::SessionView,
+};
+
+pub fn run(
+    db: FileDB,
+    config: Config,
+    session: &SessionArgs,
+    width: u16,
+    height: u16,
+    format: SnapshotFormat,
+    output: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    // Layers and the region selection only show on the inspect screen, and a
+    // headless frame can't be navigated there afterwards like the TUI can.
+    if session.view != SessionView::Inspect && (session.layer.is_some() || session.region.is_some())
+    {
+        return Err("--layer and --region need --view inspect".into());
+    }
+    let app = headless_app(db, config, session.to_session())?;
+    // Map exports reuse the area the map was drawn into, so always draw first.
+    let buffer = render_buffer(&app, width, height)?;
+    let rendered = match format {
+        SnapshotFormat::Text => to_text(&buffer).into_bytes(),
+        SnapshotFormat::Ansi => to_ansi(&buffer).into_bytes(),
+        SnapshotFormat::Html => to_html(&buffer).into_bytes(),
+        SnapshotFormat::Svg => app.view.map_export(&app.model, MapFormat::Svg)?,
+        SnapshotFormat::Png => app.view.map_export(&app.model, MapFormat::Png)?,
+    };
+    match output {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => std::io::stdout().write_all(&rendered)?,
+    }
+    Ok(())
+}
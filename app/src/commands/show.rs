@@ -0,0 +1,136 @@
+use std::error::Error;
+
+use crate::{
+    cli::Format,
+    commands::table::print_table,
+    db::{db_connection::DBConnection, file_db::FileDB},
+    domain::{
+        geometry::{Local, Polygon},
+        lines::LineCollection,
+        poi::PoiCollection,
+    },
+    state::SavedState,
+};
+
+pub fn run(db: &FileDB, id: &String, format: Format) -> Result<(), Box<dyn Error>> {
+    let location = db.get_by_id(id)?;
+    let starred = SavedState::load().is_starred(id);
+
+    match format {
+        Format::Json => {
+            let mut out = location.tag.to_json(starred);
+            out["boundaries"] = location.boundaries.iter().map(region_json).collect();
+            out["regions"] = location.regions.iter().map(region_json).collect();
+            out["pois"] = location.pois.iter().map(PoiCollection::to_json).collect();
+            out["lines"] = location.lines.iter().map(LineCollection::to_json).collect();
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        Format::Table => {
+            let tag = &location.tag;
+            let fields = [
+                ("ID", tag.id.clone()),
+                ("Name", tag.name.clone()),
+                (
+                    "Country",
+                    format!("{}/{}", tag.country_code, tag.country_subdivision),
+                ),
+                ("Type", tag.kind.clone()),
+                ("Status", tag.status.clone()),
+                ("Created", tag.created_date.clone()),
+                ("Coord", format!("{:.4}, {:.4}", tag.coord.x, tag.coord.y)),
+                ("Starred", if starred { "yes" } else { "no" }.to_string()),
+            ];
+            for (label, value) in fields {
+                println!("{:<9}{}", format!("{}:", label), value);
+            }
+            println!();
+            let rows: Vec<Vec<String>> = location
+                .boundaries
+                .iter()
+                .map(|p| region_row(p, "boundary"))
+                .chain(location.regions.iter().map(|p| region_row(p, "region")))
+                .collect();
+            print_table(
+                &["ROLE", "ID", "NAME", "KIND", "CATEGORY", "VERTICES"],
+                &rows,
+            );
+            if !location.pois.is_empty() {
+                println!();
+                print_poi_table(&location.pois);
+            }
+            if !location.lines.is_empty() {
+                println!();
+                print_line_table(&location.lines);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One row per POI, with its collection.
+pub fn print_poi_table(collections: &[PoiCollection]) {
+    let rows: Vec<Vec<String>> = collections
+        .iter()
+        .flat_map(|c| {
+            c.pois.iter().map(move |p| {
+                vec![
+                    c.id.clone(),
+                    c.category.clone(),
+                    p.id.clone(),
+                    p.name.clone(),
+                    format!("{:.0}", p.point.x),
+                    format!("{:.0}", p.point.y),
+                ]
+            })
+        })
+        .collect();
+    print_table(&["COLLECTION", "CATEGORY", "ID", "NAME", "X", "Y"], &rows);
+}
+
+/// One row per line collection, with its style and size.
+pub fn print_line_table(collections: &[LineCollection]) {
+    let rows: Vec<Vec<String>> = collections
+        .iter()
+        .map(|c| {
+            vec![
+                c.id.clone(),
+                c.name.clone(),
+                c.style
+                    .color
+                    .map_or_else(|| "-".to_string(), |c| c.to_string()),
+                c.style.dash.label().to_string(),
+                c.lines.len().to_string(),
+                c.lines
+                    .iter()
+                    .map(|l| l.inner.0.len())
+                    .sum::<usize>()
+                    .to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        &["COLLECTION", "NAME", "COLOR", "DASH", "LINES", "VERTICES"],
+        &rows,
+    );
+}
+
+fn region_json(poly: &Polygon<Local>) -> serde_json::Value {
+    serde_json::json!({
+        "id": poly.metadata.id,
+        "name": poly.metadata.name,
+        "kind": poly.metadata.kind,
+        "category": poly.metadata.category,
+        "vertices": poly.inner.exterior().0.len(),
+    })
+}
+
+fn region_row(poly: &Polygon<Local>, role: &str) -> Vec<String> {
+    vec![
+        role.to_string(),
+        poly.metadata.id.clone(),
+        poly.metadata.name.clone(),
+        poly.metadata.kind.clone().unwrap_or_default(),
+        poly.metadata.category.clone(),
+        poly.inner.exterior().0.len().to_string(),
+    ]
+}
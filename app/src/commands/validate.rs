@@ -0,0 +1,60 @@
+use std::error::Error;
+
+use crate::{
+    cli::Format,
+    commands::table::print_table,
+    db::{
+        file_db::FileDB,
+        validation::{Diagnostic, Severity},
+    },
+};
+
+/// Validate the whole dataset, print every diagnostic, and exit non-zero if
+/// any of them is an error.
+pub fn run(db: &FileDB, format: Format) -> Result<(), Box<dyn Error>> {
+    let diagnostics = db.validate();
+
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&diagnostics)?),
+        Format::Table => {
+            let rows: Vec<Vec<String>> = diagnostics.iter().map(diagnostic_row).collect();
+            print_table(
+                &["SEVERITY", "LOCATION", "REGION", "WHERE", "MESSAGE"],
+                &rows,
+            );
+            let errors = count(&diagnostics, Severity::Error);
+            let warnings = count(&diagnostics, Severity::Warning);
+            println!("\n{} error(s), {} warning(s)", errors, warnings);
+        }
+    }
+
+    if count(&diagnostics, Severity::Error) > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn count(diagnostics: &[Diagnostic], severity: Severity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|d| d.severity == severity)
+        .count()
+}
+
+fn diagnostic_row(d: &Diagnostic) -> Vec<String> {
+    let severity = match d.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let location = match d.line {
+        Some(line) => format!("{}:{}", d.file.display(), line),
+        None => d.file.display().to_string(),
+    };
+    vec![
+        severity.to_string(),
+        d.location_id.clone(),
+        d.region_id.clone().unwrap_or_default(),
+        location,
+        d.message.clone(),
+    ]
+}
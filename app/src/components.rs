@@ -1 +1,6 @@
+pub mod copy_flash;
+pub mod error_popup;
 pub mod map_view;
+pub mod measure;
+pub mod path_prompt;
+pub mod toast;
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+};
+
+use crate::{
+    clipboard::{self, ClipboardMethod, CopiedVia},
+    error::ErrorReport,
+    theme::Theme,
+};
+
+const FLASH_DURATION: Duration = Duration::from_millis(300);
+/// The outcome line stays up longer than the highlight so it can be read.
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+
+struct Flash<F> {
+    field: F,
+    result: Result<CopiedVia, ErrorReport>,
+    at: Instant,
+}
+
+/// Copies text to the clipboard and remembers the outcome, so a screen can
+/// briefly highlight what was copied (`F` names the thing) and say where it
+/// went.
+pub struct CopyFlash<F> {
+    flash: Option<Flash<F>>,
+}
+
+impl<F: PartialEq + Copy> CopyFlash<F> {
+    pub fn new() -> Self {
+        Self { flash: None }
+    }
+
+    pub fn copy(&mut self, field: F, value: &str, method: ClipboardMethod) {
+        self.flash = Some(Flash {
+            field,
+            result: clipboard::copy(value, method).map_err(|e| ErrorReport::new(&e)),
+            at: Instant::now(),
+        });
+    }
+
+    /// The theme's success colour behind a field just copied, its error
+    /// colour if the copy failed.
+    pub fn style(&self, field: F, theme: &Theme) -> Style {
+        match &self.flash {
+            Some(f) if f.field == field && f.at.elapsed() < FLASH_DURATION => {
+                let bg = if f.result.is_ok() {
+                    theme.success
+                } else {
+                    theme.error
+                };
+                Style::new().bg(bg).fg(Color::Black)
+            }
+            _ => Style::new(),
+        }
+    }
+
+    /// Which clipboard the last copy went to, or why it failed. Sized for a
+    /// block's bottom border.
+    pub fn status(&self, theme: &Theme) -> Option<Line<'static>> {
+        let flash = self
+            .flash
+            .as_ref()
+            .filter(|f| f.at.elapsed() < STATUS_DURATION)?;
+        Some(match &flash.result {
+            Ok(via) => Line::styled(
+                format!(" Copied via {} ", via.label()),
+                Style::new().fg(theme.success),
+            ),
+            Err(err) => Line::styled(
+                format!(" Copy failed: {} ", err.one_line()),
+                Style::new().fg(theme.error),
+            ),
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{error::ErrorReport, theme::Theme};
+
+/// Draw an error over the centre of `area`: the message in bold, then one
+/// "caused by" line per source, with any hint in the bottom border. Dismissed
+/// by the next key press.
+pub fn render_error_popup(frame: &mut Frame, area: Rect, err: &ErrorReport, theme: &Theme) {
+    let mut lines = err.lines.iter();
+    let mut text: Vec<Line> = lines
+        .next()
+        .map(|first| {
+            Line::from(Span::styled(
+                first.as_str(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ))
+        })
+        .into_iter()
+        .collect();
+    text.extend(lines.map(|l| {
+        Line::from(vec![
+            Span::styled("caused by: ", Style::new().add_modifier(Modifier::DIM)),
+            Span::raw(l.as_str()),
+        ])
+    }));
+
+    let width = area.width.saturating_mul(2) / 3;
+    // Borders plus a rough allowance for wrapping long lines.
+    let height = (text.len() as u16 * 2 + 2).min(area.height);
+    let [popup] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(popup);
+
+    let mut block = Block::bordered()
+        .title("Error")
+        .border_style(Style::new().fg(theme.error));
+    if let Some(hint) = &err.hint {
+        block = block.title_bottom(Line::from(format!(" {} ", hint)).right_aligned());
+    }
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+        popup,
+    );
+}
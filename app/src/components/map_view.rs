@@ -1,80 +1,37 @@
-use geo::Coord;
+use geo::{Coord, Intersects, LineString};
 use itertools::Itertools;
 use std::{
+    cell::{Cell, RefCell},
     collections::{BTreeMap, HashMap},
     marker::PhantomData,
 };
 
 use ratatui::{
+    layout::{Position, Rect},
     style::{Color, Modifier, Style},
     symbols::Marker,
-    text::Span,
+    text::{self, Span},
     widgets::{
-        canvas::{Canvas, Context, Line, Points},
-        Block, Borders,
+        canvas::{Canvas, Line, Painter, Points, Shape},
+        Block, Borders, Clear, Paragraph,
     },
 };
 
 use crate::{
     component::Component,
-    domain::geometry::{Point, Polygon, Polyline, Projection},
+    domain::{
+        geometry::{Point, Polygon, Polyline, Projection, RegionMetadata, WGS84},
+        lines::Dash,
+        region_index::RegionIndex,
+        simplify::{LineLevels, PolygonLevels},
+    },
+    components::measure::{Measure, Measurement},
+    glyphs::GlyphSet,
     message::Message,
+    state::Viewport,
+    theme::Theme,
 };
 
-/// 256-entry lookup mapping an octant bitmask to its rendering character.
-/// Bit numbering is row-major over a 2x4 sub-cell grid:
-///   bit 0 (mask   1) = (col 0, row 0) top-left
-///   bit 1 (mask   2) = (col 1, row 0) top-right
-///   bit 2 (mask   4) = (col 0, row 1)
-///   bit 3 (mask   8) = (col 1, row 1)
-///   bit 4 (mask  16) = (col 0, row 2)
-///   bit 5 (mask  32) = (col 1, row 2)
-///   bit 6 (mask  64) = (col 0, row 3) bottom-left
-///   bit 7 (mask 128) = (col 1, row 3) bottom-right
-///
-/// Most entries are from the Unicode 16 "Block Octants" block (U+1CD00..U+1CDE5);
-/// remaining entries fall back to legacy quadrants (U+2596..U+259F), half/quarter
-/// blocks (U+2580..U+2590), and a few characters from the Symbols for Legacy
-/// Computing Supplement (U+1CEA0..) and Symbols for Legacy Computing (U+1FB80..).
-///
-/// **Terminal caveat**: requires Unicode 16-aware fonts (Sept 2024). Modern
-/// terminals (kitty, recent iTerm2, WezTerm, Ghostty) render the full table;
-/// older terminals will show tofu for the U+1CDxx and U+1CExx ranges.
-const OCTANT_TABLE: [&str; 256] = [
-    "\u{0020}", "\u{1CEA8}", "\u{1CEAB}", "\u{1FB82}", "\u{1CD00}", "\u{2598}", "\u{1CD01}", "\u{1CD02}",
-    "\u{1CD03}", "\u{1CD04}", "\u{259D}", "\u{1CD05}", "\u{1CD06}", "\u{1CD07}", "\u{1CD08}", "\u{2580}",
-    "\u{1CD09}", "\u{1CD0A}", "\u{1CD0B}", "\u{1CD0C}", "\u{1FBE6}", "\u{1CD0D}", "\u{1CD0E}", "\u{1CD0F}",
-    "\u{1CD10}", "\u{1CD11}", "\u{1CD12}", "\u{1CD13}", "\u{1CD14}", "\u{1CD15}", "\u{1CD16}", "\u{1CD17}",
-    "\u{1CD18}", "\u{1CD19}", "\u{1CD1A}", "\u{1CD1B}", "\u{1CD1C}", "\u{1CD1D}", "\u{1CD1E}", "\u{1CD1F}",
-    "\u{1FBE7}", "\u{1CD20}", "\u{1CD21}", "\u{1CD22}", "\u{1CD23}", "\u{1CD24}", "\u{1CD25}", "\u{1CD26}",
-    "\u{1CD27}", "\u{1CD28}", "\u{1CD29}", "\u{1CD2A}", "\u{1CD2B}", "\u{1CD2C}", "\u{1CD2D}", "\u{1CD2E}",
-    "\u{1CD2F}", "\u{1CD30}", "\u{1CD31}", "\u{1CD32}", "\u{1CD33}", "\u{1CD34}", "\u{1CD35}", "\u{1FB85}",
-    "\u{1CEA3}", "\u{1CD36}", "\u{1CD37}", "\u{1CD38}", "\u{1CD39}", "\u{1CD3A}", "\u{1CD3B}", "\u{1CD3C}",
-    "\u{1CD3D}", "\u{1CD3E}", "\u{1CD3F}", "\u{1CD40}", "\u{1CD41}", "\u{1CD42}", "\u{1CD43}", "\u{1CD44}",
-    "\u{2596}", "\u{1CD45}", "\u{1CD46}", "\u{1CD47}", "\u{1CD48}", "\u{258C}", "\u{1CD49}", "\u{1CD4A}",
-    "\u{1CD4B}", "\u{1CD4C}", "\u{259E}", "\u{1CD4D}", "\u{1CD4E}", "\u{1CD4F}", "\u{1CD50}", "\u{259B}",
-    "\u{1CD51}", "\u{1CD52}", "\u{1CD53}", "\u{1CD54}", "\u{1CD55}", "\u{1CD56}", "\u{1CD57}", "\u{1CD58}",
-    "\u{1CD59}", "\u{1CD5A}", "\u{1CD5B}", "\u{1CD5C}", "\u{1CD5D}", "\u{1CD5E}", "\u{1CD5F}", "\u{1CD60}",
-    "\u{1CD61}", "\u{1CD62}", "\u{1CD63}", "\u{1CD64}", "\u{1CD65}", "\u{1CD66}", "\u{1CD67}", "\u{1CD68}",
-    "\u{1CD69}", "\u{1CD6A}", "\u{1CD6B}", "\u{1CD6C}", "\u{1CD6D}", "\u{1CD6E}", "\u{1CD6F}", "\u{1CD70}",
-    "\u{1CEA0}", "\u{1CD71}", "\u{1CD72}", "\u{1CD73}", "\u{1CD74}", "\u{1CD75}", "\u{1CD76}", "\u{1CD77}",
-    "\u{1CD78}", "\u{1CD79}", "\u{1CD7A}", "\u{1CD7B}", "\u{1CD7C}", "\u{1CD7D}", "\u{1CD7E}", "\u{1CD7F}",
-    "\u{1CD80}", "\u{1CD81}", "\u{1CD82}", "\u{1CD83}", "\u{1CD84}", "\u{1CD85}", "\u{1CD86}", "\u{1CD87}",
-    "\u{1CD88}", "\u{1CD89}", "\u{1CD8A}", "\u{1CD8B}", "\u{1CD8C}", "\u{1CD8D}", "\u{1CD8E}", "\u{1CD8F}",
-    "\u{2597}", "\u{1CD90}", "\u{1CD91}", "\u{1CD92}", "\u{1CD93}", "\u{259A}", "\u{1CD94}", "\u{1CD95}",
-    "\u{1CD96}", "\u{1CD97}", "\u{2590}", "\u{1CD98}", "\u{1CD99}", "\u{1CD9A}", "\u{1CD9B}", "\u{259C}",
-    "\u{1CD9C}", "\u{1CD9D}", "\u{1CD9E}", "\u{1CD9F}", "\u{1CDA0}", "\u{1CDA1}", "\u{1CDA2}", "\u{1CDA3}",
-    "\u{1CDA4}", "\u{1CDA5}", "\u{1CDA6}", "\u{1CDA7}", "\u{1CDA8}", "\u{1CDA9}", "\u{1CDAA}", "\u{1CDAB}",
-    "\u{2582}", "\u{1CDAC}", "\u{1CDAD}", "\u{1CDAE}", "\u{1CDAF}", "\u{1CDB0}", "\u{1CDB1}", "\u{1CDB2}",
-    "\u{1CDB3}", "\u{1CDB4}", "\u{1CDB5}", "\u{1CDB6}", "\u{1CDB7}", "\u{1CDB8}", "\u{1CDB9}", "\u{1CDBA}",
-    "\u{1CDBB}", "\u{1CDBC}", "\u{1CDBD}", "\u{1CDBE}", "\u{1CDBF}", "\u{1CDC0}", "\u{1CDC1}", "\u{1CDC2}",
-    "\u{1CDC3}", "\u{1CDC4}", "\u{1CDC5}", "\u{1CDC6}", "\u{1CDC7}", "\u{1CDC8}", "\u{1CDC9}", "\u{1CDCA}",
-    "\u{1CDCB}", "\u{1CDCC}", "\u{1CDCD}", "\u{1CDCE}", "\u{1CDCF}", "\u{1CDD0}", "\u{1CDD1}", "\u{1CDD2}",
-    "\u{1CDD3}", "\u{1CDD4}", "\u{1CDD5}", "\u{1CDD6}", "\u{1CDD7}", "\u{1CDD8}", "\u{1CDD9}", "\u{1CDDA}",
-    "\u{2584}", "\u{1CDDB}", "\u{1CDDC}", "\u{1CDDD}", "\u{1CDDE}", "\u{2599}", "\u{1CDDF}", "\u{1CDE0}",
-    "\u{1CDE1}", "\u{1CDE2}", "\u{259F}", "\u{1CDE3}", "\u{2586}", "\u{1CDE4}", "\u{1CDE5}", "\u{2588}",
-];
-
 #[derive(Clone)]
 pub struct ColorMap {
     stops: Vec<(f64, (u8, u8, u8))>,
@@ -122,31 +79,98 @@ pub struct MapView<P: Projection + 'static> {
     pub offset_x: f64,
     pub offset_y: f64,
     pub scale: f64,
-    pub background: &'static [Polyline<P>],
+    pub background: Option<&'static LineLevels<P>>,
     pub show_location: bool,
     pub center_on: bool,
+    /// Where the map was last drawn, so exports can reproduce the on-screen view.
+    last_area: Cell<Rect>,
+    /// The crosshair, while shown. Moved by keys or the mouse.
+    cursor: Option<Point<P>>,
+    /// The path being measured, in measure mode.
+    measure: Option<Measure<P>>,
+    /// Fills as last drawn, redone only when they or the viewport change.
+    fills: RefCell<Option<FillRaster>>,
     _proj: PhantomData<P>,
 }
 
 pub struct FillByValue {
+    pub label: String,
     pub map: ColorMap,
     pub values: HashMap<String, f64>,
 }
 
+impl FillByValue {
+    pub fn max_value(&self) -> Option<f64> {
+        self.values.values().copied().reduce(f64::max)
+    }
+
+    /// Colour for a region, scaled so the largest value hits the top of the map.
+    /// `None` if the region has no value.
+    pub fn color_for(&self, region_id: &str, max_value: Option<f64>) -> Option<Color> {
+        self.fraction_for(region_id, max_value)
+            .map(|t| self.map.sample(t))
+    }
+
+    /// A region's value as a fraction of the largest one.
+    pub fn fraction_for(&self, region_id: &str, max_value: Option<f64>) -> Option<f64> {
+        self.values
+            .get(region_id)
+            .map(|v| v / max_value.unwrap_or(1.0))
+    }
+}
+
+/// A point drawn with its own glyph rather than merged into the dot grid,
+/// e.g. a POI.
+pub struct MapMarker<'a, P: Projection> {
+    pub at: Point<P>,
+    pub glyph: &'static str,
+    pub label: &'a str,
+    pub selected: bool,
+}
+
+/// A collection of lines drawn alike, e.g. a river or a rail line.
+pub struct MapLines<'a, P: Projection> {
+    pub lines: &'a [Polyline<P>],
+    pub color: Color,
+    pub dash: Dash,
+}
+
 pub struct MapViewCtx<'a, P: Projection> {
     pub center: &'a Point<P>,
     pub boundaries: &'a [Polygon<P>],
     pub regions: &'a [Polygon<P>],
-    pub polylines: &'a [Polyline<P>],
+    /// An index over `regions`, to skip those off screen. Without one
+    /// every region is looked at.
+    pub region_index: Option<&'a RegionIndex>,
+    /// Simplified copies of `boundaries` and `regions`, drawn instead when
+    /// zoomed out far enough.
+    pub levels: Option<&'a PolygonLevels<P>>,
+    /// The `generation` of the location `regions` belong to, or 0 with none.
+    pub generation: u64,
+    pub polylines: &'a [MapLines<'a, P>],
     pub points: &'a [Point<P>],
+    pub markers: &'a [MapMarker<'a, P>],
     pub title: &'a str,
     pub selected_region: &'a Option<usize>,
+    /// Regions outlined alongside the selected one, e.g. its neighbours.
+    pub neighbours: &'a [usize],
     pub fill_info: Option<FillByValue>,
+    /// Where a Local map's origin is on the globe, for the cursor's lat/lng.
+    pub origin: Option<&'a Point<WGS84>>,
+    pub theme: Theme,
+}
+
+/// What the cursor is over, topmost first.
+enum CursorHit<'a> {
+    Marker(&'a str),
+    Point,
+    Region(&'a RegionMetadata),
+    Boundary(&'a RegionMetadata),
 }
 
 impl<P: Projection + 'static> MapView<P> {
     pub fn new(
-        background: &'static [Polyline<P>],
+        background: Option<&'static LineLevels<P>>,
         scale: Option<f64>,
         show_location: bool,
         center_on: bool,
@@ -158,10 +182,29 @@ impl<P: Projection + 'static> MapView<P> {
             background,
             show_location,
             center_on,
+            last_area: Cell::new(Rect::default()),
+            cursor: None,
+            measure: None,
+            fills: RefCell::new(None),
             _proj: PhantomData,
         }
     }
 
+    /// World-space `(x_bounds, y_bounds)` the canvas spans when drawn into `area`.
+    pub fn bounds(&self, area: Rect, center: &Point<P>) -> ([f64; 2], [f64; 2]) {
+        let center_x = if self.center_on { center.x } else { 0.0 };
+        let center_y = if self.center_on { center.y } else { 0.0 };
+        let cx = center_x + self.offset_x;
+        let cy = center_y + self.offset_y;
+        let half_x = (area.width as f64 / 2.0) * P::UNITS_PER_CELL_X * self.scale;
+        let half_y = (area.height as f64 / 2.0) * P::UNITS_PER_CELL_Y * self.scale;
+        ([cx - half_x, cx + half_x], [cy - half_y, cy + half_y])
+    }
+
+    pub fn last_area(&self) -> Rect {
+        self.last_area.get()
+    }
+
     /// Reset offsets to origin and set scale so the given polygons fit the viewport
     /// with a small margin. Polygons are assumed to be in this MapView's projection.
     pub fn fit_polygons(&mut self, boundaries: &[Polygon<P>], regions: &[Polygon<P>]) {
@@ -177,6 +220,65 @@ impl<P: Projection + 'static> MapView<P> {
         self.offset_x = 0.0;
         self.offset_y = 0.0;
     }
+
+    pub fn cursor(&self) -> Option<Point<P>> {
+        self.cursor
+    }
+
+    /// Hide the cursor, leaving measure mode too.
+    pub fn hide_cursor(&mut self) {
+        self.cursor = None;
+        self.measure = None;
+    }
+
+    /// What the path so far measures, in measure mode.
+    pub fn measurement(&self) -> Option<Measurement> {
+        self.measure.as_ref().map(Measure::measurement)
+    }
+
+    /// The world point drawn in terminal cell `(column, row)`, if that cell
+    /// is inside the map as last drawn.
+    fn point_at(&self, column: u16, row: u16, center: &Point<P>) -> Option<Point<P>> {
+        let area = self.last_area.get();
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        if !inner.contains(Position::new(column, row)) {
+            return None;
+        }
+        let (x_bounds, y_bounds) = self.bounds(area, center);
+        let (x, y) = cell_center_world(
+            i64::from(column - inner.x),
+            i64::from(row - inner.y),
+            x_bounds,
+            y_bounds,
+            inner.width,
+            inner.height,
+        );
+        Some(Point::new(x, y))
+    }
+
+    /// World size of one terminal cell at the current zoom.
+    pub fn cell_size(&self) -> (f64, f64) {
+        (
+            P::UNITS_PER_CELL_X * self.scale,
+            P::UNITS_PER_CELL_Y * self.scale,
+        )
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        Viewport {
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+            scale: Some(self.scale),
+        }
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        self.offset_x = viewport.offset_x;
+        self.offset_y = viewport.offset_y;
+        if let Some(scale) = viewport.scale {
+            self.scale = scale;
+        }
+    }
 }
 
 impl<P: Projection + 'static> Component for MapView<P> {
@@ -185,7 +287,7 @@ impl<P: Projection + 'static> Component for MapView<P> {
     fn update<'a>(
         &mut self,
         msg: &crate::message::Message,
-        _ctx: Self::Ctx<'a>,
+        ctx: Self::Ctx<'a>,
         _db: &crate::db::file_db::FileDB,
     ) -> (Vec<crate::update::Update>, Vec<Message>) {
         // pan: N screen-cells per press, projection-aware
@@ -195,13 +297,66 @@ impl<P: Projection + 'static> Component for MapView<P> {
         // zoom: symmetric multiplicative factor (in-then-out returns to start)
         const ZOOM_FACTOR: f64 = 1.0 / 0.9;
 
-        match msg {
-            Message::Char('w') | Message::Char('k') | Message::Up => self.offset_y += pan_y,
-            Message::Char('s') | Message::Char('j') | Message::Down => self.offset_y -= pan_y,
-            Message::Char('a') | Message::Char('h') | Message::Left => self.offset_x -= pan_x,
-            Message::Char('d') | Message::Char('l') | Message::Right => self.offset_x += pan_x,
-            Message::Char('+') => self.scale /= ZOOM_FACTOR,
-            Message::Char('-') => self.scale *= ZOOM_FACTOR,
+        let (x_bounds, y_bounds) = self.bounds(self.last_area.get(), ctx.center);
+        let middle = Point::new(
+            (x_bounds[0] + x_bounds[1]) / 2.0,
+            (y_bounds[0] + y_bounds[1]) / 2.0,
+        );
+        let cell = self.cell_size();
+        let step = match msg {
+            Message::Char('w') | Message::Char('k') | Message::Up => Some((0.0, 1.0)),
+            Message::Char('s') | Message::Char('j') | Message::Down => Some((0.0, -1.0)),
+            Message::Char('a') | Message::Char('h') | Message::Left => Some((-1.0, 0.0)),
+            Message::Char('d') | Message::Char('l') | Message::Right => Some((1.0, 0.0)),
+            _ => None,
+        };
+        match (msg, step, &mut self.cursor) {
+            // While the cursor is shown, movement keys move it a cell at a
+            // time, and the map pans only once it reaches an edge.
+            (_, Some((sx, sy)), Some(cursor)) => {
+                cursor.x += sx * cell.0;
+                cursor.y += sy * cell.1;
+                if cursor.x < x_bounds[0] || cursor.x > x_bounds[1] {
+                    self.offset_x += sx * pan_x;
+                }
+                if cursor.y < y_bounds[0] || cursor.y > y_bounds[1] {
+                    self.offset_y += sy * pan_y;
+                }
+            }
+            (_, Some((sx, sy)), None) => {
+                self.offset_x += sx * pan_x;
+                self.offset_y += sy * pan_y;
+            }
+            (Message::Char('x'), _, Some(_)) => self.hide_cursor(),
+            (Message::Char('x'), _, None) => self.cursor = Some(middle),
+            (Message::Char('m'), _, _) if self.measure.is_some() => self.measure = None,
+            (Message::Char('m'), _, cursor) => {
+                self.measure = Some(Measure::new());
+                cursor.get_or_insert(middle);
+            }
+            (Message::Enter, _, Some(cursor)) => {
+                if let Some(measure) = &mut self.measure {
+                    measure.push(*cursor, cell);
+                }
+            }
+            (Message::Backspace, _, _) => {
+                if let Some(measure) = &mut self.measure {
+                    measure.undo();
+                }
+            }
+            (Message::Mouse { column, row, click }, _, _) => {
+                // Leaving the map hides the cursor, except while measuring,
+                // where it stays put so Enter can still add it to the path.
+                let at = self.point_at(*column, *row, ctx.center);
+                if at.is_some() || self.measure.is_none() {
+                    self.cursor = at;
+                }
+                if let (true, Some(at), Some(measure)) = (click, self.cursor, &mut self.measure) {
+                    measure.push(at, cell);
+                }
+            }
+            (Message::Char('+'), _, _) => self.scale /= ZOOM_FACTOR,
+            (Message::Char('-'), _, _) => self.scale *= ZOOM_FACTOR,
             _ => (),
         };
         (vec![], vec![])
@@ -213,30 +368,97 @@ impl<P: Projection + 'static> Component for MapView<P> {
         area: ratatui::prelude::Rect,
         ctx: Self::Ctx<'a>,
     ) {
-        let center_x = if self.center_on { ctx.center.x } else { 0.0 };
-        let center_y = if self.center_on { ctx.center.y } else { 0.0 };
-        let cx = center_x + self.offset_x;
-        let cy = center_y + self.offset_y;
-        let half_x = (area.width as f64 / 2.0) * P::UNITS_PER_CELL_X * self.scale;
-        let half_y = (area.height as f64 / 2.0) * P::UNITS_PER_CELL_Y * self.scale;
-        let x_bounds = [cx - half_x, cx + half_x];
-        let y_bounds = [cy - half_y, cy + half_y];
+        self.last_area.set(area);
+        let (x_bounds, y_bounds) = self.bounds(area, ctx.center);
 
-        let max_fill_value = match &ctx.fill_info {
-            Some(fill_info) => fill_info.values.values().copied().reduce(f64::max),
-            None => None,
-        };
-        let block = Block::default().borders(Borders::ALL).title(ctx.title);
+        let max_fill_value = ctx.fill_info.as_ref().and_then(|fi| fi.max_value());
+        let mut block = Block::default().borders(Borders::ALL).title(ctx.title);
+        if let Some(measurement) = self.measurement() {
+            block = block.title_bottom(format!(
+                "Measure: {} - Enter/click add, Backspace undo, y copy, m done",
+                measurement
+            ));
+        }
         // The canvas paints inside the block's borders; point glyphs must be
         // bucketed against this inner grid, not the full widget area.
         let inner = block.inner(area);
+        // World size of one braille dot, the canvas's resolution.
+        let dot = (
+            (x_bounds[1] - x_bounds[0]) / (f64::from(inner.width.max(1)) * 2.0),
+            (y_bounds[1] - y_bounds[0]) / (f64::from(inner.height.max(1)) * 4.0),
+        );
+        // Detail finer than a dot can't be seen, so draw the coarsest copies
+        // that stay within one.
+        let tolerance = dot.0.min(dot.1);
+        let level = ctx.levels.and_then(|l| l.at(tolerance));
+        let (boundaries, regions) = match level {
+            Some(level) => (level.boundaries.as_slice(), level.regions.as_slice()),
+            None => (ctx.boundaries, ctx.regions),
+        };
+        let background = self.background.map_or(&[][..], |b| b.at(tolerance));
+
+        let theme = &ctx.theme;
+        let visible: Vec<usize> = match ctx.region_index {
+            Some(index) => index.in_bounds(x_bounds, y_bounds),
+            None => (0..regions.len()).collect(),
+        };
+        let mut fills: Vec<Fill> = vec![];
+        let mut outlined = vec![];
+        let mut selected = vec![];
+        for i in visible {
+            let poly = &regions[i];
+            if ctx.selected_region == &Some(i) {
+                selected.push(i);
+                continue;
+            }
+            let fill = ctx.fill_info.as_ref().and_then(|fi| {
+                let t = fi.fraction_for(&poly.metadata.id, max_fill_value)?;
+                Some((fi.map.sample(t), t))
+            });
+            if let Some((color, t)) = fill {
+                let pattern = if theme.patterns {
+                    FillPattern::Density(MIN_DENSITY + (1.0 - MIN_DENSITY) * t)
+                } else {
+                    FillPattern::Solid
+                };
+                fills.push((i, color, pattern));
+            }
+            // Sparse pattern fills don't show a region's shape on their own,
+            // so those keep their outline too.
+            if fill.is_none() || theme.patterns {
+                outlined.push(poly);
+            }
+        }
+        let selection_pattern = if theme.patterns {
+            FillPattern::Hatch
+        } else {
+            FillPattern::Solid
+        };
+        fills.extend(
+            selected
+                .iter()
+                .map(|&i| (i, theme.selection, selection_pattern)),
+        );
+        let key = FillKey {
+            x_bounds,
+            y_bounds,
+            size: (inner.width, inner.height),
+            generation: ctx.generation,
+            level: level.map(|l| l.tolerance),
+            fills,
+        };
+        if self.fills.borrow().as_ref().is_none_or(|f| f.key != key) {
+            self.fills.replace(Some(FillRaster::new(key, regions)));
+        }
+        let fill_raster = self.fills.borrow();
+
         let canvas = Canvas::default()
             .block(block)
             .marker(Marker::Braille)
             .x_bounds(x_bounds)
             .y_bounds(y_bounds)
             .paint(|c| {
-                for poly in ctx.boundaries {
+                for poly in boundaries {
                     for (a, b) in poly.inner.exterior().coords().tuple_windows() {
                         if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
                             c.draw(&Line {
@@ -244,48 +466,30 @@ impl<P: Projection + 'static> Component for MapView<P> {
                                 y1,
                                 x2,
                                 y2,
-                                color: Color::Red,
+                                color: theme.boundary,
                             });
                         }
                     }
                 }
 
-                let mut selected_polys = vec![];
-                for (i, poly) in ctx.regions.iter().enumerate() {
-                    let selected = ctx.selected_region == &Some(i);
-                    if selected {
-                        selected_polys.push(poly);
-                        continue;
-                    }
-                    let fill_color = ctx.fill_info.as_ref().and_then(|fi| {
-                        fi.values
-                            .get(&poly.metadata.id)
-                            .map(|v| fi.map.sample(v / max_fill_value.unwrap_or(1.0)))
-                    });
-                    match fill_color {
-                        Some(color) => {
-                            fill_polygon::<P>(c, poly, color, x_bounds, y_bounds, self.scale)
-                        }
-                        None => {
-                            for (a, b) in poly.inner.exterior().coords().tuple_windows() {
-                                if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds)
-                                {
-                                    c.draw(&Line {
-                                        x1,
-                                        y1,
-                                        x2,
-                                        y2,
-                                        color: Color::Red,
-                                    });
-                                }
-                            }
+                if let Some(raster) = fill_raster.as_ref() {
+                    c.draw(raster);
+                }
+                for poly in &outlined {
+                    for (a, b) in poly.inner.exterior().coords().tuple_windows() {
+                        if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
+                            c.draw(&Line {
+                                x1,
+                                y1,
+                                x2,
+                                y2,
+                                color: theme.region,
+                            });
                         }
                     }
                 }
 
-                for poly in selected_polys {
-                    let color = Color::Green;
-                    fill_polygon::<P>(c, poly, color, x_bounds, y_bounds, self.scale);
+                for poly in ctx.neighbours.iter().filter_map(|&i| regions.get(i)) {
                     for (a, b) in poly.inner.exterior().coords().tuple_windows() {
                         if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
                             c.draw(&Line {
@@ -293,25 +497,51 @@ impl<P: Projection + 'static> Component for MapView<P> {
                                 y1,
                                 x2,
                                 y2,
-                                color,
+                                color: theme.selection,
                             });
                         }
                     }
                 }
-                for line in ctx.polylines {
-                    for (a, b) in line.inner.coords().tuple_windows() {
+
+                for poly in selected.iter().map(|&i| &regions[i]) {
+                    for (a, b) in poly.inner.exterior().coords().tuple_windows() {
                         if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
                             c.draw(&Line {
                                 x1,
                                 y1,
                                 x2,
                                 y2,
-                                color: Color::Red,
+                                color: theme.selection,
                             });
                         }
                     }
                 }
-                for line in self.background {
+                for collection in ctx.polylines {
+                    let color = collection.color;
+                    for line in collection.lines {
+                        if let Some(pattern) = dash_pattern(collection.dash) {
+                            let coords =
+                                dash_points(&line.inner, pattern, x_bounds, y_bounds, dot);
+                            c.draw(&Points {
+                                coords: &coords,
+                                color,
+                            });
+                            continue;
+                        }
+                        for (a, b) in line.inner.coords().tuple_windows() {
+                            if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
+                                c.draw(&Line {
+                                    x1,
+                                    y1,
+                                    x2,
+                                    y2,
+                                    color,
+                                });
+                            }
+                        }
+                    }
+                }
+                for line in background {
                     for (a, b) in line.inner.coords().tuple_windows() {
                         if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
                             c.draw(&Line {
@@ -319,13 +549,13 @@ impl<P: Projection + 'static> Component for MapView<P> {
                                 y1,
                                 x2,
                                 y2,
-                                color: Color::Green,
+                                color: theme.coastline,
                             });
                         }
                     }
                 }
 
-                // Accumulate octant bits per terminal cell so multiple points in
+                // Accumulate sub-cell bits per terminal cell so multiple points in
                 // the same cell combine into a single composite glyph. Cells are
                 // keyed by ratatui's label transform so each glyph lands in
                 // exactly the cell it was bucketed into; BTreeMap keeps the
@@ -344,37 +574,270 @@ impl<P: Projection + 'static> Component for MapView<P> {
                     }
                     let (world_x, world_y) =
                         cell_center_world(col, row, x_bounds, y_bounds, inner.width, inner.height);
-                    let glyph = OCTANT_TABLE[*mask as usize];
+                    let glyph = theme.glyphs.glyph(*mask);
                     c.print(
                         world_x,
                         world_y,
                         Span::styled(
                             glyph,
-                            Style::new()
-                                .fg(Color::Rgb(255, 255, 255))
-                                .add_modifier(Modifier::BOLD),
+                            Style::new().fg(theme.point).add_modifier(Modifier::BOLD),
                         ),
                     );
                 }
 
+                // Selected last so it stays on top of its neighbours.
+                let markers = ctx.markers.iter().filter(|m| !m.selected);
+                for marker in markers.chain(ctx.markers.iter().filter(|m| m.selected)) {
+                    let (x, y) = (marker.at.x, marker.at.y);
+                    if x < x_bounds[0] || x > x_bounds[1] || y < y_bounds[0] || y > y_bounds[1] {
+                        continue;
+                    }
+                    let style = if marker.selected {
+                        Style::new().fg(theme.selection).add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::new().fg(theme.point)
+                    };
+                    c.print(x, y, Span::styled(marker.glyph, style.add_modifier(Modifier::BOLD)));
+                }
+
+                if let Some(measure) = &self.measure {
+                    let mut path = measure.path();
+                    // Until it's closed, the path stretches on to the cursor.
+                    if let (false, Some(cursor)) = (measure.closed, &self.cursor) {
+                        path.push(Coord {
+                            x: cursor.x,
+                            y: cursor.y,
+                        });
+                    }
+                    for (a, b) in path.iter().tuple_windows() {
+                        if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
+                            c.draw(&Line {
+                                x1,
+                                y1,
+                                x2,
+                                y2,
+                                color: theme.accent,
+                            });
+                        }
+                    }
+                    let glyph = if theme.glyphs == GlyphSet::Ascii {
+                        "o"
+                    } else {
+                        "•"
+                    };
+                    for vertex in &measure.vertices {
+                        c.print(
+                            vertex.x,
+                            vertex.y,
+                            Span::styled(glyph, Style::new().fg(theme.accent).bold()),
+                        );
+                    }
+                }
+
+                if let Some(cursor) = &self.cursor {
+                    let glyph = if theme.glyphs == GlyphSet::Ascii {
+                        "+"
+                    } else {
+                        "╋"
+                    };
+                    c.print(
+                        cursor.x,
+                        cursor.y,
+                        Span::styled(glyph, Style::new().fg(theme.accent).bold()),
+                    );
+                }
+
                 if self.show_location {
                     c.print(
                         ctx.center.x,
                         ctx.center.y,
-                        Span::styled("X", Style::new().red().bold()),
+                        Span::styled("X", Style::new().fg(theme.marker).bold()),
                     );
                 }
             });
 
         frame.render_widget(canvas, area);
+
+        if let Some(cursor) = self.cursor {
+            let cell = (
+                P::UNITS_PER_CELL_X * self.scale,
+                P::UNITS_PER_CELL_Y * self.scale,
+            );
+            if let Some(((col, row), _)) =
+                octant_bit_for_point(cursor.x, cursor.y, x_bounds, y_bounds, inner.width, inner.height)
+            {
+                let lines = tooltip_lines(cursor, hit_test(cursor, &ctx, cell), &ctx);
+                let at = Position::new(inner.x + col as u16, inner.y + row as u16);
+                render_tooltip(frame, inner, at, lines, &ctx.theme);
+            }
+        }
+    }
+}
+
+/// The marker, point, region or boundary under `at`. Markers and points
+/// count when they're in about the same cell.
+fn hit_test<'a, P: Projection>(
+    at: Point<P>,
+    ctx: &MapViewCtx<'a, P>,
+    cell: (f64, f64),
+) -> Option<CursorHit<'a>> {
+    let near =
+        |p: &Point<P>| (p.x - at.x).abs() <= cell.0 / 2.0 && (p.y - at.y).abs() <= cell.1 / 2.0;
+    let coord = Coord { x: at.x, y: at.y };
+    if let Some(marker) = ctx.markers.iter().find(|m| near(&m.at)) {
+        return Some(CursorHit::Marker(marker.label));
+    }
+    if ctx.points.iter().any(near) {
+        return Some(CursorHit::Point);
+    }
+    let region = match ctx.region_index {
+        Some(index) => index.at(ctx.regions, at.x, at.y),
+        None => ctx.regions.iter().position(|r| r.inner.intersects(&coord)),
+    };
+    if let Some(i) = region {
+        return Some(CursorHit::Region(&ctx.regions[i].metadata));
+    }
+    ctx.boundaries
+        .iter()
+        .find(|b| b.inner.intersects(&coord))
+        .map(|b| CursorHit::Boundary(&b.metadata))
+}
+
+/// What's under the cursor, then where it is: in metres for a Local map and
+/// in lat/lng whenever that's known.
+fn tooltip_lines<'a, P: Projection>(
+    at: Point<P>,
+    hit: Option<CursorHit<'a>>,
+    ctx: &MapViewCtx<'a, P>,
+) -> Vec<text::Line<'a>> {
+    let theme = &ctx.theme;
+    let key = |k: &str| Span::styled(format!("{}: ", k), Style::new().fg(theme.accent));
+    let title = |name: &'a str, what: &'static str| {
+        text::Line::from(vec![
+            Span::styled(name, Style::new().bold()),
+            Span::styled(format!(" {}", what), Style::new().add_modifier(Modifier::DIM)),
+        ])
+    };
+    let mut lines = vec![];
+    match hit {
+        Some(CursorHit::Marker(label)) => lines.push(title(label, "marker")),
+        Some(CursorHit::Point) => lines.push(title("Point", "")),
+        Some(CursorHit::Region(meta) | CursorHit::Boundary(meta)) => {
+            let what = match hit {
+                Some(CursorHit::Region(_)) => "region",
+                _ => "boundary",
+            };
+            lines.push(title(&meta.name, what));
+            lines.push(text::Line::from(vec![key("id"), Span::raw(meta.id.as_str())]));
+            if let Some(kind) = &meta.kind {
+                lines.push(text::Line::from(vec![key("kind"), Span::raw(kind.as_str())]));
+            }
+            lines.push(text::Line::from(vec![
+                key("category"),
+                Span::raw(meta.category.as_str()),
+            ]));
+            if let (Some(fill), Some(CursorHit::Region(_))) = (&ctx.fill_info, &hit) {
+                let value = match fill.values.get(&meta.id) {
+                    Some(v) => format!("{:.2}", v),
+                    None => "-".to_string(),
+                };
+                lines.push(text::Line::from(vec![key(&fill.label), Span::raw(value)]));
+            }
+        }
+        None => (),
+    }
+    if P::METRES {
+        lines.push(text::Line::from(vec![
+            key("local"),
+            Span::raw(format!("{:.0}, {:.0} m", at.x, at.y)),
+        ]));
+    }
+    let lnglat = match ctx.origin {
+        Some(origin) => Some(P::to_wgs84(at.x, at.y, origin)),
+        None if !P::METRES => Some((at.x, at.y)),
+        None => None,
+    };
+    if let Some((lng, lat)) = lnglat {
+        lines.push(text::Line::from(vec![
+            key("lat/lng"),
+            Span::raw(format!("{:.5}, {:.5}", lat, lng)),
+        ]));
+    }
+    lines
+}
+
+/// A bordered box beside the cursor cell `at`, flipped left or up when it
+/// would run off `area`.
+fn render_tooltip(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    at: Position,
+    lines: Vec<text::Line>,
+    theme: &Theme,
+) {
+    let width = (lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let x = if at.x + 2 + width <= area.right() {
+        at.x + 2
+    } else {
+        at.x.saturating_sub(width + 1).max(area.x)
+    };
+    let y = if at.y + 1 + height <= area.bottom() {
+        at.y + 1
+    } else {
+        at.y.saturating_sub(height).max(area.y)
+    };
+    let popup = Rect::new(x, y, width, height);
+    let block = Block::bordered().border_style(Style::new().fg(theme.accent));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// `(on, off)` run lengths in canvas dots, or `None` for a solid line.
+pub fn dash_pattern(dash: Dash) -> Option<(f64, f64)> {
+    match dash {
+        Dash::Solid => None,
+        Dash::Dashed => Some((4.0, 3.0)),
+        Dash::Dotted => Some((1.0, 2.0)),
+    }
+}
+
+/// Sample the visible part of a line at one point per canvas dot, keeping
+/// those that fall in the pattern's "on" runs. Distance is counted in dots,
+/// so dashes keep their length at any zoom, and carried from one segment to
+/// the next so the pattern doesn't restart at every vertex.
+fn dash_points(
+    line: &LineString,
+    (on, off): (f64, f64),
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+    dot: (f64, f64),
+) -> Vec<(f64, f64)> {
+    let dots = |dx: f64, dy: f64| ((dx / dot.0).powi(2) + (dy / dot.1).powi(2)).sqrt();
+    let mut points = vec![];
+    let mut travelled = 0.0;
+    for (a, b) in line.coords().tuple_windows() {
+        if let Some([x1, y1, x2, y2]) = clip_line(a, b, x_bounds, y_bounds) {
+            let start = travelled + dots(x1 - a.x, y1 - a.y);
+            let visible = dots(x2 - x1, y2 - y1);
+            let steps = visible.ceil().max(1.0) as usize;
+            for k in 0..=steps {
+                let t = k as f64 / steps as f64;
+                if (start + t * visible) % (on + off) < on {
+                    points.push((x1 + t * (x2 - x1), y1 + t * (y2 - y1)));
+                }
+            }
+        }
+        travelled += dots(b.x - a.x, b.y - a.y);
     }
+    points
 }
 
 /// Locate a continuous (px, py) point at its precise sub-cell position. Returns
 /// the terminal cell index plus an octant bitmask with a single bit set for that
 /// point's sub-cell. Returns `None` if the point is outside the canvas bounds.
 /// Caller OR-combines bits from multiple points falling in the same cell, then
-/// looks up `OCTANT_TABLE[mask as usize]` for the composite glyph.
+/// asks the theme's [`GlyphSet`](crate::glyphs::GlyphSet) for the composite glyph.
 ///
 /// Mirrors ratatui's `Painter::get_point` at braille resolution (2x4 dots per
 /// cell), `dot = ((x - left) * (dots - 1) / span).round()`, so each point marks
@@ -434,7 +897,7 @@ fn cell_center_world(
 
 /// Clip a line segment from `a` to `b` to the rectangle `[x_bounds] × [y_bounds]`
 /// using the Liang-Barsky algorithm. Returns `None` if the segment is entirely outside.
-fn clip_line(a: &Coord, b: &Coord, x_bounds: [f64; 2], y_bounds: [f64; 2]) -> Option<[f64; 4]> {
+pub fn clip_line(a: &Coord, b: &Coord, x_bounds: [f64; 2], y_bounds: [f64; 2]) -> Option<[f64; 4]> {
     let dx = b.x - a.x;
     let dy = b.y - a.y;
 
@@ -480,57 +943,141 @@ fn clip_line(a: &Coord, b: &Coord, x_bounds: [f64; 2], y_bounds: [f64; 2]) -> Op
     Some([a.x + t0 * dx, a.y + t0 * dy, a.x + t1 * dx, a.y + t1 * dy])
 }
 
-/// Scanline-fill a polygon onto a Canvas Context using the even-odd rule.
-/// Steps in braille sub-cell increments (2 dots × 4 dots per terminal cell).
-fn fill_polygon<P: Projection>(
-    c: &mut Context,
-    poly: &Polygon<P>,
-    color: Color,
+/// Share of dots lit for the smallest value in pattern mode, so a region
+/// with a value of zero still differs from one with no value.
+const MIN_DENSITY: f64 = 0.1;
+
+/// 4x4 ordered-dither thresholds: lighting dots whose entry is below
+/// `density * 16` spreads any density evenly over the area.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Which dots a fill lights.
+#[derive(Clone, Copy, PartialEq)]
+enum FillPattern {
+    Solid,
+    /// This fraction of dots, evenly spread.
+    Density(f64),
+    /// Diagonal lines, distinct from any density.
+    Hatch,
+}
+
+impl FillPattern {
+    /// Whether the dot at column `i`, row `j` of the sub-cell grid is lit.
+    fn lit(self, i: i64, j: i64) -> bool {
+        match self {
+            FillPattern::Solid => true,
+            FillPattern::Density(d) => {
+                let threshold = BAYER[j.rem_euclid(4) as usize][i.rem_euclid(4) as usize];
+                (threshold as f64 + 0.5) / 16.0 < d
+            }
+            FillPattern::Hatch => (i + j).rem_euclid(4) == 0,
+        }
+    }
+}
+
+/// A region to fill: its index, its colour and its pattern.
+type Fill = (usize, Color, FillPattern);
+
+/// What a [`FillRaster`] was drawn from. Regions are compared by index, so
+/// the location and simplification level they were drawn at are part of it.
+#[derive(PartialEq)]
+struct FillKey {
     x_bounds: [f64; 2],
     y_bounds: [f64; 2],
-    scale: f64,
-) {
-    let coords: Vec<&Coord> = poly.inner.exterior().coords().collect();
-    if coords.len() < 3 {
-        return;
+    /// Canvas size in cells.
+    size: (u16, u16),
+    generation: u64,
+    /// Tolerance of the simplified level drawn, `None` for the originals.
+    level: Option<f64>,
+    fills: Vec<Fill>,
+}
+
+/// Region fills scanlined onto the canvas's braille dot grid, kept between
+/// draws so an unchanged map isn't filled again every tick.
+struct FillRaster {
+    key: FillKey,
+    /// Dot columns per row.
+    width: usize,
+    /// Each dot's colour, row by row from the top-left. Later fills win.
+    dots: Vec<Option<Color>>,
+}
+
+impl FillRaster {
+    fn new<P: Projection>(key: FillKey, regions: &[Polygon<P>]) -> Self {
+        let width = usize::from(key.size.0) * 2;
+        let height = usize::from(key.size.1) * 4;
+        let mut raster = Self {
+            key,
+            width,
+            dots: vec![None; width * height],
+        };
+        for i in 0..raster.key.fills.len() {
+            let (region, color, pattern) = raster.key.fills[i];
+            raster.fill(&regions[region], color, pattern);
+        }
+        raster
     }
-    let (y_min, y_max) = coords
-        .iter()
-        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| {
-            (lo.min(c.y), hi.max(c.y))
-        });
-    let y_min = y_min.max(y_bounds[0]);
-    let y_max = y_max.min(y_bounds[1]);
-
-    let dx = P::UNITS_PER_CELL_X * scale / 2.0;
-    let dy = P::UNITS_PER_CELL_Y * scale / 4.0;
-
-    let mut points: Vec<(f64, f64)> = Vec::new();
-    let mut y = y_min;
-    while y <= y_max {
+
+    /// Scanline-fill a polygon using the even-odd rule, one scanline per dot
+    /// row through the dot centres the canvas itself would light. Patterns
+    /// are laid on the dot grid anchored to the viewport's top-left, so they
+    /// stay regular across neighbouring polygons.
+    fn fill<P: Projection>(&mut self, poly: &Polygon<P>, color: Color, pattern: FillPattern) {
+        let height = self.dots.len() / self.width.max(1);
+        let coords: Vec<&Coord> = poly.inner.exterior().coords().collect();
+        if coords.len() < 3 || self.width < 2 || height < 2 {
+            return;
+        }
+        let [left, right] = self.key.x_bounds;
+        let [bottom, top] = self.key.y_bounds;
+        let dx = (right - left) / (self.width - 1) as f64;
+        let dy = (top - bottom) / (height - 1) as f64;
+        let (y_min, y_max) = coords
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| {
+                (lo.min(c.y), hi.max(c.y))
+            });
+        let first_row = ((top - y_max) / dy).ceil().max(0.0) as usize;
+        let last_row = ((top - y_min) / dy).floor().min((height - 1) as f64);
+        if last_row < first_row as f64 {
+            return;
+        }
+
         let mut xs: Vec<f64> = Vec::new();
-        for (a, b) in coords.iter().tuple_windows() {
-            if (a.y > y) != (b.y > y) {
-                let t = (y - a.y) / (b.y - a.y);
-                xs.push(a.x + t * (b.x - a.x));
+        for j in first_row..=last_row as usize {
+            let y = top - j as f64 * dy;
+            xs.clear();
+            for (a, b) in coords.iter().tuple_windows() {
+                if (a.y > y) != (b.y > y) {
+                    let t = (y - a.y) / (b.y - a.y);
+                    xs.push(a.x + t * (b.x - a.x));
+                }
             }
-        }
-        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        for pair in xs.chunks(2) {
-            if let &[x_start, x_end] = pair {
-                let x0 = x_start.max(x_bounds[0]);
-                let x1 = x_end.min(x_bounds[1]);
-                let mut x = x0;
-                while x <= x1 {
-                    points.push((x, y));
-                    x += dx;
+            xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            for pair in xs.chunks(2) {
+                if let &[x_start, x_end] = pair {
+                    let first = ((x_start - left) / dx).ceil().max(0.0);
+                    let last = ((x_end - left) / dx).floor().min((self.width - 1) as f64);
+                    if last < first {
+                        continue;
+                    }
+                    for i in first as usize..=last as usize {
+                        if pattern.lit(i as i64, j as i64) {
+                            self.dots[j * self.width + i] = Some(color);
+                        }
+                    }
                 }
             }
         }
-        y += dy;
     }
-    c.draw(&Points {
-        coords: &points,
-        color,
-    });
+}
+
+impl Shape for FillRaster {
+    fn draw(&self, painter: &mut Painter) {
+        for (k, dot) in self.dots.iter().enumerate() {
+            if let Some(color) = dot {
+                painter.paint(k % self.width, k / self.width, *color);
+            }
+        }
+    }
 }
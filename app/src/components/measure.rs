@@ -0,0 +1,97 @@
+use std::fmt;
+
+use geo::{Coord, LineString, Polygon as GeoPolygon};
+
+use crate::domain::geometry::{Point, Projection};
+
+/// A path being measured on a map: vertices dropped at the cursor, closed
+/// into a shape when the last one lands back on the first.
+pub struct Measure<P: Projection> {
+    pub vertices: Vec<Point<P>>,
+    pub closed: bool,
+}
+
+/// A measured path's length in metres, and its area in square metres once
+/// closed.
+pub struct Measurement {
+    pub length: f64,
+    pub area: Option<f64>,
+}
+
+impl<P: Projection> Measure<P> {
+    pub fn new() -> Self {
+        Self {
+            vertices: vec![],
+            closed: false,
+        }
+    }
+
+    /// Drop a vertex at `at`. Dropping one within a `cell` of the first
+    /// vertex closes the path instead, once it has three; dropping one after
+    /// that starts a new path.
+    pub fn push(&mut self, at: Point<P>, cell: (f64, f64)) {
+        if self.closed {
+            self.vertices.clear();
+            self.closed = false;
+        }
+        if let [first, _, _, ..] = self.vertices[..] {
+            if (first.x - at.x).abs() <= cell.0 / 2.0 && (first.y - at.y).abs() <= cell.1 / 2.0 {
+                self.closed = true;
+                return;
+            }
+        }
+        self.vertices.push(at);
+    }
+
+    /// Reopen a closed path, or take back the last vertex.
+    pub fn undo(&mut self) {
+        if self.closed {
+            self.closed = false;
+        } else {
+            self.vertices.pop();
+        }
+    }
+
+    /// The path's vertices, back to the first again if it is closed.
+    pub fn path(&self) -> Vec<Coord> {
+        let mut path: Vec<Coord> = self
+            .vertices
+            .iter()
+            .map(|p| Coord { x: p.x, y: p.y })
+            .collect();
+        if self.closed {
+            path.push(path[0]);
+        }
+        path
+    }
+
+    pub fn measurement(&self) -> Measurement {
+        let line = LineString::new(self.path());
+        Measurement {
+            // An empty sum is -0.0, which would print as "-0 m".
+            length: if line.0.len() < 2 {
+                0.0
+            } else {
+                P::length(&line)
+            },
+            area: self
+                .closed
+                .then(|| P::area(&GeoPolygon::new(line.clone(), vec![]))),
+        }
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.length >= 1000.0 {
+            write!(f, "{:.2} km", self.length / 1000.0)?;
+        } else {
+            write!(f, "{:.0} m", self.length)?;
+        }
+        match self.area {
+            Some(area) if area >= 1_000_000.0 => write!(f, ", area {:.2} km²", area / 1_000_000.0),
+            Some(area) => write!(f, ", area {:.0} m²", area),
+            None => Ok(()),
+        }
+    }
+}
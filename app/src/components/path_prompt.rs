@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{message::Message, theme::Theme};
+
+pub enum PromptOutcome {
+    Editing,
+    Cancelled,
+    Submitted(PathBuf),
+}
+
+/// A one-line prompt for an output file, drawn along the bottom of an area.
+/// Enter on a file that already exists asks again before overwriting it.
+pub struct PathPrompt {
+    title: String,
+    path: String,
+    confirm_overwrite: bool,
+}
+
+impl PathPrompt {
+    pub fn new(title: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            path: path.into(),
+            confirm_overwrite: false,
+        }
+    }
+
+    pub fn update(&mut self, msg: &Message) -> PromptOutcome {
+        match msg {
+            Message::Char(c) => {
+                self.path.push(*c);
+                self.confirm_overwrite = false;
+            }
+            Message::Backspace => {
+                self.path.pop();
+                self.confirm_overwrite = false;
+            }
+            Message::Esc => return PromptOutcome::Cancelled,
+            Message::Enter if !self.path.is_empty() => {
+                let path = PathBuf::from(&self.path);
+                if !path.exists() || self.confirm_overwrite {
+                    return PromptOutcome::Submitted(path);
+                }
+                self.confirm_overwrite = true;
+            }
+            _ => (),
+        }
+        PromptOutcome::Editing
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let [popup] = Layout::vertical([Constraint::Length(3)])
+            .flex(Flex::End)
+            .areas(area);
+        let block = if self.confirm_overwrite {
+            Block::bordered()
+                .title("File exists - Enter again to overwrite it, Esc to cancel")
+                .border_style(Style::new().fg(theme.error))
+        } else {
+            Block::bordered()
+                .title(format!("{} - Enter to save, Esc to cancel", self.title))
+                .border_style(Style::new().fg(theme.accent))
+        };
+        frame.render_widget(Clear, popup);
+        frame.render_widget(
+            Paragraph::new(format!("{}_", self.path)).block(block),
+            popup,
+        );
+    }
+}
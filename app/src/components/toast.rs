@@ -0,0 +1,31 @@
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    widgets::{Block, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{model::Toast, theme::Theme};
+
+/// Draw a one-line notice in the bottom-right corner of `area` until it expires.
+pub fn render_toast(frame: &mut Frame, area: Rect, toast: &Toast, theme: &Theme) {
+    if !toast.visible() {
+        return;
+    }
+    let width = (toast.message.chars().count() as u16 + 4).min(area.width);
+    let [popup] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::End)
+        .areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(3)])
+        .flex(Flex::End)
+        .areas(popup);
+
+    let color = if toast.error {
+        theme.error
+    } else {
+        theme.success
+    };
+    let block = Block::bordered().border_style(Style::new().fg(color));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(toast.message.as_str()).block(block), popup);
+}
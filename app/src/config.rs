@@ -1,47 +1,394 @@
-use std::path::PathBuf;
+pub mod validation;
 
-#[derive(serde::Deserialize)]
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use crate::{
+    cli::ConfigArgs,
+    clipboard::ClipboardMethod,
+    color::ColorDepth,
+    error::ConfigError,
+    glyphs::GlyphSet,
+    theme::{FillStyle, Theme, ThemeConfig},
+};
+
+use self::validation::{ConfigProblem, SourceFile};
+
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct Config {
     pub ui: UiConfig,
     pub data: DataConfig,
 
     #[serde(default)]
     pub layers: Vec<LayerConfig>,
+
+    #[serde(default)]
+    pub theme: ThemeConfig,
+
+    /// Where this config came from, so it can be loaded again the same way.
+    #[serde(skip)]
+    pub source: ConfigSource,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct UiConfig {
     pub region_label: String,
+    /// `auto`, `native` or `osc52`.
+    #[serde(default)]
+    pub clipboard: ClipboardMethod,
+    /// `auto`, `truecolor`, `256`, `16` or `none`.
+    #[serde(default)]
+    pub color_depth: ColorDepth,
+    /// `auto`, `color` or `pattern`.
+    #[serde(default)]
+    pub fill_style: FillStyle,
+    /// `auto`, `octant`, `braille`, `quadrant` or `ascii`.
+    #[serde(default)]
+    pub glyphs: GlyphSet,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct DataConfig {
     pub root_dir: PathBuf,
 }
 
-#[derive(serde::Deserialize, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
 pub struct LayerConfig {
     pub id: String,
     pub name: String,
+    /// Empty for a built-in layer.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub command: String,
     #[serde(default = "default_timeout_secs")]
     pub timeout_secs: u64,
+    #[serde(default)]
+    pub kind: LayerKind,
+    /// Computed in-process instead of by a command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builtin: Option<BuiltinLayer>,
+    /// The layer a `neighbour_mean` layer averages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// What a layer command prints, set by a layer's `kind`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerKind {
+    /// A number per region id, for filling regions.
+    #[default]
+    Values,
+    /// A list of POI collections, drawn as markers.
+    Pois,
+    /// A list of line collections, drawn over the regions.
+    Lines,
+}
+
+/// Values worked out from the region polygons, with no command to run.
+/// Always `values` layers, whatever `kind` says.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinLayer {
+    /// km².
+    Area,
+    /// km, holes included.
+    Perimeter,
+    /// Polsby-Popper score, 0 to 1.
+    Compactness,
+    /// km from the location's centre to the region's centroid.
+    CentreDistance,
+    /// How many regions share a border with it.
+    Neighbours,
+    /// The mean of the `source` layer's values over the regions sharing a
+    /// border with it.
+    NeighbourMean,
+}
+
+/// The defaults' layers: one per [`BuiltinLayer`] except `neighbour_mean`,
+/// which needs a source, under the same id as its `builtin` value, so a
+/// config can rename one like any other layer.
+fn builtin_layers() -> Vec<LayerConfig> {
+    [
+        (BuiltinLayer::Area, "area", "Area (km²)"),
+        (BuiltinLayer::Perimeter, "perimeter", "Perimeter (km)"),
+        (BuiltinLayer::Compactness, "compactness", "Compactness"),
+        (
+            BuiltinLayer::CentreDistance,
+            "centre_distance",
+            "Distance from centre (km)",
+        ),
+        (BuiltinLayer::Neighbours, "neighbours", "Neighbours"),
+    ]
+    .into_iter()
+    .map(|(builtin, id, name)| LayerConfig {
+        id: id.to_string(),
+        name: name.to_string(),
+        command: String::new(),
+        timeout_secs: default_timeout_secs(),
+        kind: LayerKind::Values,
+        builtin: Some(builtin),
+        source: None,
+    })
+    .collect()
 }
 
 fn default_timeout_secs() -> u64 {
     30
 }
 
+#[derive(Default)]
+pub struct ConfigSource {
+    pub args: ConfigArgs,
+    /// Every file read, including those pulled in by `extends`, base first.
+    pub files: Vec<PathBuf>,
+}
+
+/// Longest `extends` chain followed before assuming a cycle.
+const MAX_EXTENDS_DEPTH: usize = 16;
+
+impl Config {
+    /// The config files to load, most general first: `--config` flags if
+    /// given, else the paths in `LOCTUI_CONFIG`, else
+    /// `~/.config/loctui/config.toml` if it exists.
+    pub fn paths(args: &ConfigArgs) -> Vec<PathBuf> {
+        if !args.configs.is_empty() {
+            return args.configs.clone();
+        }
+        if let Some(paths) = std::env::var_os("LOCTUI_CONFIG") {
+            return std::env::split_paths(&paths).collect();
+        }
+        dirs::config_dir()
+            .map(|dir| dir.join("loctui").join("config.toml"))
+            .filter(|path| path.is_file())
+            .into_iter()
+            .collect()
+    }
+
+    /// Layer the config files over the built-in defaults, apply the chosen
+    /// `[profile.NAME]` section and any `--data-root` override, then validate
+    /// the result. Every problem found is reported together, each pointing at
+    /// the file and line it comes from.
+    pub fn load(args: &ConfigArgs) -> Result<Self, ConfigError> {
+        let mut merged = Table::try_from(Config::default()).unwrap_or_default();
+        let mut sources = vec![];
+        for path in Self::paths(args) {
+            let table = read_layer(&path, &mut sources, 0)?;
+            merge(&mut merged, table);
+        }
+
+        let profiles = match merged.remove("profile") {
+            Some(Value::Table(profiles)) => profiles,
+            _ => Table::new(),
+        };
+        if let Some(name) = &args.profile {
+            match profiles.get(name) {
+                Some(Value::Table(profile)) => merge(&mut merged, profile.clone()),
+                _ => {
+                    return Err(ConfigError::UnknownProfile {
+                        name: name.clone(),
+                        available: profiles.keys().cloned().collect(),
+                    })
+                }
+            }
+        }
+
+        if let Some(root) = &args.data_root {
+            let data = merged
+                .entry("data")
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(data) = data {
+                data.insert(
+                    "root_dir".to_string(),
+                    Value::String(root.to_string_lossy().into_owned()),
+                );
+            }
+        }
+
+        let mut problems = validation::check_files(&sources);
+        problems.extend(validation::check_merged(&merged, args, &sources));
+        if problems.is_empty() {
+            match merged.try_into::<Config>() {
+                Ok(mut config) => {
+                    // Built-ins come from the defaults, so merge ahead of the
+                    // configured layers; list them after instead.
+                    config.layers.sort_by_key(|l| l.builtin.is_some());
+                    config.source = ConfigSource {
+                        args: args.clone(),
+                        files: sources.into_iter().map(|s| s.path).collect(),
+                    };
+                    return Ok(config);
+                }
+                // Anything the checks above don't cover, e.g. a number out
+                // of range for its field. Without a span, but still listed.
+                Err(e) => problems.push(ConfigProblem::new(e.message().to_string())),
+            }
+        }
+        Err(ConfigError::Invalid { problems })
+    }
+
+    /// The `[theme]` colours with `ui.fill_style` and `ui.glyphs` decided.
+    pub fn theme(&self) -> Theme {
+        let mut theme = self.theme.resolve();
+        theme.patterns = match self.ui.fill_style {
+            FillStyle::Color => false,
+            FillStyle::Pattern => true,
+            FillStyle::Auto => self.ui.color_depth.is_limited(),
+        };
+        theme.glyphs = self.ui.glyphs.resolve();
+        theme
+    }
+
+    /// Load again from the same files and flags, e.g. after they changed.
+    pub fn reload(&self) -> Result<Self, ConfigError> {
+        Self::load(&self.source.args)
+    }
+}
+
+/// Read one config file with relative paths made absolute, then lay it over
+/// whatever it `extends`.
+fn read_layer(
+    path: &Path,
+    sources: &mut Vec<SourceFile>,
+    depth: usize,
+) -> Result<Table, ConfigError> {
+    if depth > MAX_EXTENDS_DEPTH {
+        return Err(ConfigError::ExtendsTooDeep {
+            path: path.to_path_buf(),
+        });
+    }
+    let raw = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut table: Table = toml::from_str(&raw).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    resolve_paths(&mut table, dir);
+    if let Some(Value::Table(profiles)) = table.get_mut("profile") {
+        for (_, profile) in profiles.iter_mut() {
+            if let Value::Table(profile) = profile {
+                resolve_paths(profile, dir);
+            }
+        }
+    }
+
+    let bases: Vec<PathBuf> = match table.remove("extends") {
+        Some(Value::String(base)) => vec![dir.join(base)],
+        Some(Value::Array(bases)) => bases
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|b| dir.join(b))
+            .collect(),
+        _ => vec![],
+    };
+    let mut merged = Table::new();
+    for base in bases {
+        let base = read_layer(&base, sources, depth + 1)?;
+        merge(&mut merged, base);
+    }
+    sources.push(SourceFile {
+        path: path.to_path_buf(),
+        raw,
+    });
+    merge(&mut merged, table);
+    Ok(merged)
+}
+
+/// Make `data.root_dir` and path-like layer commands relative to the config
+/// file's directory rather than wherever loctui happens to be run from. Bare
+/// command names are left for `PATH` lookup.
+fn resolve_paths(table: &mut Table, dir: &Path) {
+    if let Some(Value::String(root)) = table
+        .get_mut("data")
+        .and_then(|d| d.as_table_mut())
+        .and_then(|d| d.get_mut("root_dir"))
+    {
+        *root = dir.join(&*root).to_string_lossy().into_owned();
+    }
+    if let Some(Value::Array(layers)) = table.get_mut("layers") {
+        for layer in layers.iter_mut().filter_map(Value::as_table_mut) {
+            if let Some(Value::String(command)) = layer.get_mut("command") {
+                if command.contains('/') {
+                    *command = dir.join(&*command).to_string_lossy().into_owned();
+                }
+            }
+        }
+    }
+}
+
+/// Lay `over` onto `base`. Tables merge key by key; `layers` merge by `id`,
+/// so an overlay can redefine one layer or add new ones; anything else is
+/// replaced outright.
+fn merge(base: &mut Table, over: Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(b)), Value::Table(o)) => merge(b, o),
+            (Some(Value::Array(b)), Value::Array(o)) if key == "layers" => merge_layers(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_layers(base: &mut Vec<Value>, over: Vec<Value>) {
+    let id = |v: &Value| v.get("id").and_then(Value::as_str).map(str::to_string);
+    for layer in over {
+        match base
+            .iter_mut()
+            .find(|b| id(b).is_some() && id(b) == id(&layer))
+        {
+            Some(Value::Table(existing)) => {
+                if let Value::Table(layer) = layer {
+                    merge(existing, layer);
+                }
+            }
+            _ => base.push(layer),
+        }
+    }
+}
+
+/// Where the dataset is looked for when no config sets `data.root_dir`: the
+/// first that exists of `~/.local/share/loctui/data`, a `data` directory next
+/// to the executable, and the example dataset under the working directory
+/// (from the repository root or `app/`). With none of them, the first, so
+/// the error names where the data should go.
+fn default_root_dir() -> PathBuf {
+    let user = dirs::data_dir().map(|d| d.join("loctui").join("data"));
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join("data")));
+    let candidates: Vec<PathBuf> = user
+        .into_iter()
+        .chain(beside_exe)
+        .chain(["app/data/example_data", "data/example_data"].map(PathBuf::from))
+        .collect();
+    candidates
+        .iter()
+        .find(|dir| dir.is_dir())
+        .unwrap_or(&candidates[0])
+        .clone()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             ui: UiConfig {
                 region_label: "Region".to_string(),
+                clipboard: ClipboardMethod::default(),
+                color_depth: ColorDepth::default(),
+                fill_style: FillStyle::default(),
+                glyphs: GlyphSet::default(),
             },
             data: DataConfig {
-                root_dir: "app/data/example_data".into(),
+                root_dir: default_root_dir(),
             },
-            layers: vec![],
+            layers: builtin_layers(),
+            theme: ThemeConfig::default(),
+            source: ConfigSource::default(),
         }
     }
 }
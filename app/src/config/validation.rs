@@ -0,0 +1,586 @@
+use std::{
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use toml::{
+    de::{DeTable, DeValue},
+    Spanned, Table, Value,
+};
+
+use crate::{cli::ConfigArgs, theme::ThemeColor};
+
+/// Layer commands taking longer than this are almost certainly a typo (e.g.
+/// milliseconds instead of seconds) rather than a real budget.
+const MAX_TIMEOUT_SECS: i64 = 3600;
+
+/// A config file as read, kept so problems can point back at a line in it.
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub raw: String,
+}
+
+/// One problem found in the config, with where it was written when that can
+/// be pinned down.
+#[derive(Debug)]
+pub struct ConfigProblem {
+    pub file: Option<PathBuf>,
+    /// 1-based line and column in `file`.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}", file.display())?;
+            if let Some((line, col)) = self.position {
+                write!(f, ":{}:{}", line, col)?;
+            }
+            write!(f, ": ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ConfigProblem {
+    pub fn new(message: String) -> Self {
+        Self {
+            file: None,
+            position: None,
+            message,
+        }
+    }
+}
+
+/// What a key's value should look like.
+enum Expect {
+    String,
+    /// One of these strings.
+    OneOf(&'static [&'static str]),
+    /// A colour name, 0-255 index or `#rrggbb`.
+    Color,
+    Integer,
+    /// A string or an array of strings, as `extends` takes.
+    Strings,
+    Table(&'static [Field]),
+    /// `[[name]]` entries, each a table with these fields.
+    TableArray(&'static [Field]),
+    /// A table of named tables with these fields, as `[profile.NAME]`.
+    NamedTables(&'static [Field]),
+}
+
+impl Expect {
+    fn describe(&self) -> &'static str {
+        match self {
+            Expect::String | Expect::OneOf(_) | Expect::Color => "a string",
+            Expect::Integer => "an integer",
+            Expect::Strings => "a string or an array of strings",
+            Expect::Table(_) | Expect::NamedTables(_) => "a table",
+            Expect::TableArray(_) => "an array of tables",
+        }
+    }
+}
+
+type Field = (&'static str, Expect);
+
+const UI: &[Field] = &[
+    ("region_label", Expect::String),
+    ("clipboard", Expect::OneOf(&["auto", "native", "osc52"])),
+    (
+        "color_depth",
+        Expect::OneOf(&["auto", "truecolor", "256", "16", "none"]),
+    ),
+    ("fill_style", Expect::OneOf(&["auto", "color", "pattern"])),
+    (
+        "glyphs",
+        Expect::OneOf(&["auto", "octant", "braille", "quadrant", "ascii"]),
+    ),
+];
+const DATA: &[Field] = &[("root_dir", Expect::String)];
+const LAYER: &[Field] = &[
+    ("id", Expect::String),
+    ("name", Expect::String),
+    ("command", Expect::String),
+    ("timeout_secs", Expect::Integer),
+    ("kind", Expect::OneOf(&["values", "pois", "lines"])),
+    (
+        "builtin",
+        Expect::OneOf(&[
+            "area",
+            "perimeter",
+            "compactness",
+            "centre_distance",
+            "neighbours",
+            "neighbour_mean",
+        ]),
+    ),
+    ("source", Expect::String),
+];
+const THEME: &[Field] = &[
+    ("preset", Expect::OneOf(&["dark", "light", "high-contrast"])),
+    ("boundary", Expect::Color),
+    ("region", Expect::Color),
+    ("polyline", Expect::Color),
+    ("coastline", Expect::Color),
+    ("selection", Expect::Color),
+    ("point", Expect::Color),
+    ("marker", Expect::Color),
+    ("accent", Expect::Color),
+    ("error", Expect::Color),
+    ("warning", Expect::Color),
+    ("success", Expect::Color),
+    ("background", Expect::Color),
+    ("foreground", Expect::Color),
+];
+/// What a `[profile.NAME]` section may override.
+const PROFILE: &[Field] = &[
+    ("ui", Expect::Table(UI)),
+    ("data", Expect::Table(DATA)),
+    ("layers", Expect::TableArray(LAYER)),
+    ("theme", Expect::Table(THEME)),
+];
+const TOP: &[Field] = &[
+    ("ui", Expect::Table(UI)),
+    ("data", Expect::Table(DATA)),
+    ("layers", Expect::TableArray(LAYER)),
+    ("theme", Expect::Table(THEME)),
+    ("extends", Expect::Strings),
+    ("profile", Expect::NamedTables(PROFILE)),
+];
+
+/// A source file parsed with spans.
+struct Document<'a> {
+    file: &'a SourceFile,
+    table: DeTable<'a>,
+}
+
+impl Document<'_> {
+    fn problem(&self, span: Range<usize>, message: String) -> ConfigProblem {
+        ConfigProblem {
+            file: Some(self.file.path.clone()),
+            position: Some(position(&self.file.raw, span.start)),
+            message,
+        }
+    }
+}
+
+fn find_layer<'t>(scope: &'t DeTable<'_>, id: &str) -> Option<&'t DeTable<'t>> {
+    scope
+        .get("layers")?
+        .get_ref()
+        .as_array()?
+        .iter()
+        .filter_map(|l| l.get_ref().as_table())
+        .find(|l| l.get("id").and_then(|v| v.get_ref().as_str()) == Some(id))
+}
+
+/// 1-based line and column of a byte offset.
+fn position(raw: &str, offset: usize) -> (usize, usize) {
+    let before = &raw[..offset.min(raw.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, col)
+}
+
+/// Check every file on its own: unknown keys, values of the wrong type and
+/// layer ids repeated within one `[[layers]]` list.
+pub fn check_files(sources: &[SourceFile]) -> Vec<ConfigProblem> {
+    let mut out = vec![];
+    for doc in parse(sources) {
+        let mut found = vec![];
+        check_table(&doc, &doc.table, TOP, "", &mut found);
+        found.sort_by_key(|p| p.position);
+        out.extend(found);
+    }
+    out
+}
+
+fn parse(sources: &[SourceFile]) -> Vec<Document<'_>> {
+    sources
+        .iter()
+        // Each file has already been parsed once by the loader, so this only
+        // fails if it changed in between; its problems will show next load.
+        .filter_map(|file| {
+            let table = DeTable::parse(&file.raw).ok()?.into_inner();
+            Some(Document { file, table })
+        })
+        .collect()
+}
+
+fn check_table(
+    doc: &Document,
+    table: &DeTable,
+    fields: &[Field],
+    prefix: &str,
+    out: &mut Vec<ConfigProblem>,
+) {
+    for (key, value) in table {
+        let path = format!("{}{}", prefix, key.get_ref());
+        match fields
+            .iter()
+            .find(|(name, _)| *name == key.get_ref().as_ref())
+        {
+            Some((_, expect)) => check_value(doc, value, expect, &path, out),
+            None => {
+                let known = fields.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                out.push(doc.problem(
+                    key.span(),
+                    format!(
+                        "unknown key `{}` (expected one of: {})",
+                        path,
+                        known.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+fn check_value(
+    doc: &Document,
+    value: &Spanned<DeValue>,
+    expect: &Expect,
+    path: &str,
+    out: &mut Vec<ConfigProblem>,
+) {
+    match (expect, value.get_ref()) {
+        (Expect::String, DeValue::String(_)) | (Expect::Integer, DeValue::Integer(_)) => {}
+        (Expect::Strings, DeValue::String(_)) => {}
+        (Expect::Color, DeValue::String(v)) => {
+            if let Err(message) = v.parse::<ThemeColor>() {
+                out.push(doc.problem(value.span(), format!("`{}`: {}", path, message)));
+            }
+        }
+        (Expect::OneOf(allowed), DeValue::String(v)) => {
+            if !allowed.contains(&v.as_ref()) {
+                out.push(doc.problem(
+                    value.span(),
+                    format!("`{}` should be one of: {}", path, allowed.join(", ")),
+                ));
+            }
+        }
+        (Expect::Strings, DeValue::Array(items)) => {
+            for item in items.iter().filter(|i| !i.get_ref().is_str()) {
+                out.push(doc.problem(item.span(), format!("`{}` entries should be strings", path)));
+            }
+        }
+        (Expect::Table(fields), DeValue::Table(table)) => {
+            check_table(doc, table, fields, &format!("{}.", path), out)
+        }
+        (Expect::TableArray(fields), DeValue::Array(items)) => {
+            let mut ids: Vec<(&str, Range<usize>)> = vec![];
+            for (i, item) in items.iter().enumerate() {
+                let Some(table) = item.get_ref().as_table() else {
+                    out.push(
+                        doc.problem(item.span(), format!("`{}` entries should be tables", path)),
+                    );
+                    continue;
+                };
+                let id = table.get("id");
+                let name = id.and_then(|id| id.get_ref().as_str());
+                // Name entries by id where they have one: `layers[roads].command`.
+                let prefix = match name {
+                    Some(name) => format!("{}[{}].", path, name),
+                    None => format!("{}[{}].", path, i),
+                };
+                check_table(doc, table, fields, &prefix, out);
+                let (Some(id), Some(name)) = (id, name) else {
+                    continue;
+                };
+                if let Some((_, first)) = ids.iter().find(|(seen, _)| *seen == name) {
+                    let (line, _) = position(&doc.file.raw, first.start);
+                    out.push(doc.problem(
+                        id.span(),
+                        format!(
+                            "duplicate layer id `{}` (first defined on line {})",
+                            name, line
+                        ),
+                    ));
+                } else {
+                    ids.push((name, id.span()));
+                }
+            }
+        }
+        (Expect::NamedTables(fields), DeValue::Table(table)) => {
+            for (name, section) in table {
+                let path = format!("{}.{}", path, name.get_ref());
+                match section.get_ref() {
+                    DeValue::Table(section) => {
+                        check_table(doc, section, fields, &format!("{}.", path), out)
+                    }
+                    other => out.push(doc.problem(
+                        section.span(),
+                        format!("`{}` should be a table, not {}", path, other.type_str()),
+                    )),
+                }
+            }
+        }
+        (expect, other) => out.push(doc.problem(
+            value.span(),
+            format!(
+                "`{}` should be {}, not {}",
+                path,
+                expect.describe(),
+                other.type_str()
+            ),
+        )),
+    }
+}
+
+/// Check what the merged config refers to: that the dataset directory exists,
+/// each layer is complete, its command can be run, any layer it averages
+/// exists and its timeout is sane.
+/// Works on the merged table rather than a `Config` so these are reported
+/// alongside type errors. Problems point at the file and line that set the
+/// offending value, taking profiles and later files' precedence into account.
+pub fn check_merged(
+    merged: &Table,
+    args: &ConfigArgs,
+    sources: &[SourceFile],
+) -> Vec<ConfigProblem> {
+    let docs = parse(sources);
+    let profile = args.profile.as_deref();
+    let mut out = vec![];
+
+    let root = merged
+        .get("data")
+        .and_then(|d| d.get("root_dir"))
+        .and_then(Value::as_str);
+    if let Some(root) = root.filter(|r| !Path::new(r).is_dir()) {
+        if args.data_root.is_some() {
+            out.push(ConfigProblem::new(format!(
+                "--data-root {} is not a directory",
+                root
+            )));
+        } else {
+            out.push(locate(
+                &docs,
+                profile,
+                format!("data.root_dir {} is not a directory", root),
+                |scope| {
+                    scope
+                        .get("data")?
+                        .get_ref()
+                        .get("root_dir")
+                        .map(Spanned::span)
+                },
+            ));
+        }
+    }
+
+    let layers: Vec<&Table> = merged
+        .get("layers")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_table)
+        .collect();
+    for layer in layers.iter().copied() {
+        let Some(id) = layer.get("id").and_then(Value::as_str) else {
+            out.push(ConfigProblem::new(
+                "a [[layers]] entry has no `id`".to_string(),
+            ));
+            continue;
+        };
+        // Point at the field, or at the layer itself when the field is unset.
+        let at = |field: &'static str| {
+            move |scope: &DeTable| {
+                let entry = find_layer(scope, id)?;
+                entry
+                    .get(field)
+                    .or_else(|| entry.get("id"))
+                    .map(Spanned::span)
+            }
+        };
+        if !layer.contains_key("name") {
+            out.push(locate(
+                &docs,
+                profile,
+                format!("layer `{}` has no `name`", id),
+                at("id"),
+            ));
+        }
+        match (layer.contains_key("command"), layer.contains_key("builtin")) {
+            (false, false) => out.push(locate(
+                &docs,
+                profile,
+                format!("layer `{}` has no `command`", id),
+                at("id"),
+            )),
+            // Most likely a command layer reusing a built-in layer's id.
+            (true, true) => out.push(locate(
+                &docs,
+                profile,
+                format!(
+                    "layer `{}` has both `command` and `builtin`; built-in layers \
+                     already use the ids area, perimeter, compactness, \
+                     centre_distance and neighbours",
+                    id
+                ),
+                at("command"),
+            )),
+            _ => (),
+        }
+        // Built-in layers only compute values per region.
+        let kind = layer
+            .get("kind")
+            .and_then(Value::as_str)
+            .unwrap_or("values");
+        if layer.contains_key("builtin") && matches!(kind, "pois" | "lines") {
+            out.push(locate(
+                &docs,
+                profile,
+                format!(
+                    "layer `{}` has `builtin` with kind `{}`; built-in layers \
+                     are always `values`",
+                    id, kind
+                ),
+                at("kind"),
+            ));
+        }
+        if let Err(message) = check_source(&layers, layer, id) {
+            out.push(locate(&docs, profile, message, at("source")));
+        }
+        if let Some(command) = layer.get("command").and_then(Value::as_str) {
+            if let Err(message) = check_command(command) {
+                out.push(locate(
+                    &docs,
+                    profile,
+                    format!("layer `{}`: {}", id, message),
+                    at("command"),
+                ));
+            }
+        }
+        if let Some(timeout) = layer.get("timeout_secs").and_then(Value::as_integer) {
+            if !(1..=MAX_TIMEOUT_SECS).contains(&timeout) {
+                out.push(locate(
+                    &docs,
+                    profile,
+                    format!(
+                        "layer `{}`: timeout_secs must be between 1 and {}, not {}",
+                        id, MAX_TIMEOUT_SECS, timeout
+                    ),
+                    at("timeout_secs"),
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Attach `message` to the last place that sets a value, searching the chosen
+/// profile's sections before the top level and later files before earlier
+/// ones, the reverse of the order they were merged in.
+fn locate(
+    docs: &[Document],
+    profile: Option<&str>,
+    message: String,
+    find: impl Fn(&DeTable) -> Option<Range<usize>>,
+) -> ConfigProblem {
+    let profile_scope = |doc: &Document| -> Option<Range<usize>> {
+        let section = doc.table.get("profile")?.get_ref().get(profile?)?;
+        find(section.get_ref().as_table()?)
+    };
+    let found = docs
+        .iter()
+        .rev()
+        .find_map(|doc| profile_scope(doc).map(|span| (doc, span)))
+        .or_else(|| {
+            docs.iter()
+                .rev()
+                .find_map(|doc| find(&doc.table).map(|span| (doc, span)))
+        });
+    match found {
+        Some((doc, span)) => doc.problem(span, message),
+        None => ConfigProblem::new(message),
+    }
+}
+
+/// `source` goes with, and only with, `builtin = "neighbour_mean"`, and names
+/// a `values` layer whose own sources don't lead back to this one.
+fn check_source(layers: &[&Table], layer: &Table, id: &str) -> Result<(), String> {
+    let find = |id: &str| {
+        layers
+            .iter()
+            .find(|l| l.get("id").and_then(Value::as_str) == Some(id))
+    };
+    let source_of = |l: &Table| l.get("source").and_then(Value::as_str).map(str::to_string);
+    let averages = layer.get("builtin").and_then(Value::as_str) == Some("neighbour_mean");
+    let source = match (averages, source_of(layer)) {
+        (false, None) => return Ok(()),
+        (false, Some(_)) => {
+            return Err(format!(
+                "layer `{}` has `source`, which only `neighbour_mean` layers use",
+                id
+            ))
+        }
+        (true, None) => return Err(format!("layer `{}` averages no `source` layer", id)),
+        (true, Some(source)) => source,
+    };
+    let Some(found) = find(&source) else {
+        return Err(format!("layer `{}`: no layer `{}` to average", id, source));
+    };
+    let kind = found
+        .get("kind")
+        .and_then(Value::as_str)
+        .unwrap_or("values");
+    if kind != "values" {
+        return Err(format!(
+            "layer `{}`: source `{}` is a `{}` layer, not `values`",
+            id, source, kind
+        ));
+    }
+    // A chain of sources that doesn't loop ends within this many steps.
+    let mut next = Some(source);
+    for _ in 0..layers.len() {
+        let Some(step) = next else {
+            return Ok(());
+        };
+        if step == id {
+            return Err(format!("layer `{}`: its sources lead back to it", id));
+        }
+        next = find(&step).and_then(|l| source_of(l));
+    }
+    Ok(())
+}
+
+/// Layer commands are run directly, not through a shell: a path if it has a
+/// `/`, otherwise looked up on `PATH`.
+fn check_command(command: &str) -> Result<(), String> {
+    let path = if command.contains('/') {
+        PathBuf::from(command)
+    } else {
+        let found = std::env::var_os("PATH").and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(command))
+                .find(|p| p.is_file())
+        });
+        match found {
+            Some(path) => path,
+            None => return Err(format!("command `{}` is not on PATH", command)),
+        }
+    };
+    let Ok(meta) = std::fs::metadata(&path) else {
+        return Err(format!("command {} does not exist", path.display()));
+    };
+    if !meta.is_file() {
+        return Err(format!("command {} is not a file", path.display()));
+    }
+    if !is_executable(&meta) {
+        return Err(format!("command {} is not executable", path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    true
+}
@@ -1,2 +1,3 @@
 pub mod db_connection;
 pub mod file_db;
+pub mod validation;
@@ -1,6 +1,9 @@
-use crate::domain::location::{Location, LocationTag};
+use crate::{
+    domain::location::{Location, LocationTag},
+    error::DbError,
+};
 
 pub trait DBConnection {
-    fn get_by_id(&self, key: &String) -> Option<Location>;
+    fn get_by_id(&self, key: &String) -> Result<Location, DbError>;
     fn get_tags(&self) -> Vec<LocationTag>;
 }
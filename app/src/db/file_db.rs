@@ -1,40 +1,76 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    db::db_connection::DBConnection,
+    db::{
+        db_connection::DBConnection,
+        validation::{validate_location, Diagnostic},
+    },
     domain::location::{Location, LocationFile, LocationTag},
+    error::DbError,
 };
 
 pub struct FileDB {
-    base_path: String,
+    base_path: PathBuf,
     locations: HashMap<String, LocationFile>,
 }
 
 impl FileDB {
-    pub fn new(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut bytes = std::fs::read(path)?;
-        let entries: Vec<LocationFile> = simd_json::serde::from_slice(&mut bytes)?;
+    pub fn new(path: &Path) -> Result<Self, DbError> {
+        let mut bytes = std::fs::read(path).map_err(|source| DbError::ReadIndex {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let entries: Vec<LocationFile> =
+            simd_json::serde::from_slice(&mut bytes).map_err(|source| DbError::ParseIndex {
+                path: path.to_path_buf(),
+                source,
+            })?;
         let mut map = HashMap::new();
         for l in entries {
             map.insert(l.id.clone(), l);
         }
-        let base_path = path.parent().unwrap().to_str().unwrap();
+        // A bare file name has an empty parent, which joins as the cwd.
+        let base_path = path.parent().map(Path::to_path_buf).unwrap_or_default();
         Ok(Self {
-            base_path: String::from(base_path),
+            base_path,
             locations: map,
         })
     }
+
+    /// Check every location's polygon data, returning all problems found.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut ids: Vec<&String> = self.locations.keys().collect();
+        ids.sort();
+        ids.into_iter()
+            .flat_map(|id| validate_location(&self.locations[id], &self.base_path))
+            .collect()
+    }
 }
 
 impl DBConnection for FileDB {
     fn get_tags(&self) -> Vec<LocationTag> {
-        self.locations
+        let mut tags: Vec<LocationTag> = self
+            .locations
             .values()
             .map(|l| l.get_location_tag())
-            .collect()
+            .collect();
+        // HashMap order changes run to run; keep listings stable.
+        tags.sort_by(|a, b| a.id.cmp(&b.id));
+        tags
     }
-    fn get_by_id(&self, id: &String) -> Option<Location> {
-        let location_file = self.locations.get(id)?;
-        location_file.get_location(Path::new(&self.base_path))
+    fn get_by_id(&self, id: &String) -> Result<Location, DbError> {
+        let location_file = self
+            .locations
+            .get(id)
+            .ok_or_else(|| DbError::NotFound(id.clone()))?;
+        location_file
+            .get_location(&self.base_path)
+            .map_err(|source| DbError::Location {
+                id: id.clone(),
+                source,
+            })
     }
 }
@@ -0,0 +1,410 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use geo::{
+    line_intersection::{line_intersection, LineIntersection},
+    Coord, Intersects, Line, LineString, Polygon,
+};
+
+use crate::domain::location::{LocationFile, PolygonEntry, PolygonsFile};
+
+/// Local-frame polygons are metres around the location's centre, so a whole
+/// location spanning less than this many units almost certainly means the
+/// rings were written in degrees by mistake.
+const DEGREES_SUSPECT_SPAN: f64 = 5.0;
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in the dataset, with enough context to go and fix it.
+#[derive(serde::Serialize, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub location_id: String,
+    pub file: PathBuf,
+    /// 1-based line in `file`, when it can be pinned down.
+    pub line: Option<usize>,
+    pub region_id: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, ": {}: [{}", severity, self.location_id)?;
+        if let Some(region_id) = &self.region_id {
+            write!(f, "/{}", region_id)?;
+        }
+        write!(f, "] {}", self.message)
+    }
+}
+
+struct Reporter<'a> {
+    location_id: &'a str,
+    file: &'a Path,
+    raw: &'a str,
+    out: Vec<Diagnostic>,
+}
+
+impl Reporter<'_> {
+    fn push(
+        &mut self,
+        severity: Severity,
+        line: Option<usize>,
+        region_id: Option<&str>,
+        message: String,
+    ) {
+        self.out.push(Diagnostic {
+            severity,
+            location_id: self.location_id.to_string(),
+            file: self.file.to_path_buf(),
+            line,
+            region_id: region_id.map(str::to_string),
+            message,
+        });
+    }
+
+    fn region(&mut self, severity: Severity, entry: &PolygonEntry, message: String) {
+        let line = line_of_id(self.raw, &entry.id);
+        self.push(severity, line, Some(&entry.id), message);
+    }
+}
+
+/// Check one location's entry in `locations.json` and its polygon file.
+pub fn validate_location(location: &LocationFile, base_path: &Path) -> Vec<Diagnostic> {
+    let file = base_path.join(&location.polygon_path);
+    let raw = std::fs::read_to_string(&file);
+    let mut r = Reporter {
+        location_id: &location.id,
+        file: &file,
+        raw: raw.as_deref().unwrap_or(""),
+        out: vec![],
+    };
+
+    let [lng, lat] = location.coord;
+    if !(-180.0..=180.0).contains(&lng) || !(-90.0..=90.0).contains(&lat) {
+        r.push(
+            Severity::Error,
+            None,
+            None,
+            format!(
+                "coord [{}, {}] in locations.json is not a valid [lng, lat]",
+                lng, lat
+            ),
+        );
+    }
+
+    let raw = match &raw {
+        Ok(raw) => raw.as_str(),
+        Err(e) => {
+            r.push(
+                Severity::Error,
+                None,
+                None,
+                format!("polygon file could not be read: {}", e),
+            );
+            return r.out;
+        }
+    };
+    let parsed: PolygonsFile = match serde_json::from_str(raw) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            r.push(
+                Severity::Error,
+                Some(e.line()),
+                None,
+                format!("invalid polygon file: {}", e),
+            );
+            return r.out;
+        }
+    };
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for entry in parsed.boundaries.iter().chain(parsed.regions.iter()) {
+        *seen.entry(&entry.id).or_insert(0) += 1;
+        check_ring(&mut r, entry);
+    }
+    for (id, count) in seen {
+        if count > 1 {
+            r.push(
+                Severity::Error,
+                line_of_id(raw, id),
+                Some(id),
+                format!("id is used by {} polygons", count),
+            );
+        }
+    }
+
+    check_frame(&mut r, &parsed);
+    check_within_boundaries(&mut r, &parsed);
+    check_pois(&mut r, &parsed);
+    check_lines(&mut r, &parsed);
+
+    r.out.sort_by_key(|d| (d.severity, d.line));
+    r.out
+}
+
+fn check_ring(r: &mut Reporter, entry: &PolygonEntry) {
+    let ring = &entry.ring;
+    if ring.is_empty() {
+        r.region(Severity::Error, entry, "ring is empty".to_string());
+        return;
+    }
+    if ring.iter().flatten().any(|v| !v.is_finite()) {
+        r.region(
+            Severity::Error,
+            entry,
+            "ring contains a non-finite coordinate".to_string(),
+        );
+        return;
+    }
+    let closed = ring.first() == ring.last();
+    if !closed {
+        r.region(
+            Severity::Warning,
+            entry,
+            "ring is not closed (first and last points differ)".to_string(),
+        );
+    }
+    let distinct = ring.len() - usize::from(closed);
+    if distinct < 3 {
+        r.region(
+            Severity::Error,
+            entry,
+            format!(
+                "ring has {} distinct points; a polygon needs at least 3",
+                distinct
+            ),
+        );
+        return;
+    }
+    let mut line_string = to_line_string(ring);
+    line_string.close();
+    if let Some(at) = self_intersection(&line_string) {
+        r.region(
+            Severity::Error,
+            entry,
+            format!("ring intersects itself near ({:.1}, {:.1})", at.x, at.y),
+        );
+    }
+}
+
+/// The first point where two non-adjacent edges of a closed ring meet.
+fn self_intersection(ring: &LineString) -> Option<Coord> {
+    let edges: Vec<Line> = ring.lines().filter(|l| l.start != l.end).collect();
+    let n = edges.len();
+    for i in 0..n {
+        for j in (i + 2)..n {
+            // First and last edges share the closing vertex.
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            if !bboxes_overlap(&edges[i], &edges[j]) {
+                continue;
+            }
+            return match line_intersection(edges[i], edges[j]) {
+                Some(LineIntersection::SinglePoint { intersection, .. }) => Some(intersection),
+                // Edges lying along each other meet first where they start to.
+                Some(LineIntersection::Collinear { intersection }) => Some(intersection.start),
+                None => continue,
+            };
+        }
+    }
+    None
+}
+
+fn bboxes_overlap(a: &Line, b: &Line) -> bool {
+    a.start.x.min(a.end.x) <= b.start.x.max(b.end.x)
+        && b.start.x.min(b.end.x) <= a.start.x.max(a.end.x)
+        && a.start.y.min(a.end.y) <= b.start.y.max(b.end.y)
+        && b.start.y.min(b.end.y) <= a.start.y.max(a.end.y)
+}
+
+fn check_frame(r: &mut Reporter, parsed: &PolygonsFile) {
+    let coords = || {
+        parsed
+            .boundaries
+            .iter()
+            .chain(parsed.regions.iter())
+            .flat_map(|e| e.ring.iter())
+    };
+    if coords().next().is_none() {
+        return;
+    }
+    let looks_like_degrees = coords().all(|p| p[0].abs() <= 180.0 && p[1].abs() <= 90.0);
+    let (min, max) = coords().fold(
+        ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+        |(lo, hi), p| {
+            (
+                [lo[0].min(p[0]), lo[1].min(p[1])],
+                [hi[0].max(p[0]), hi[1].max(p[1])],
+            )
+        },
+    );
+    let span = (max[0] - min[0]).max(max[1] - min[1]);
+    if looks_like_degrees && span < DEGREES_SUSPECT_SPAN {
+        r.push(
+            Severity::Warning,
+            None,
+            None,
+            format!(
+                "all coordinates fit within a {:.3}-unit box inside lng/lat ranges; they look \
+                 like degrees, but polygons should be metres in the Local frame",
+                span
+            ),
+        );
+    }
+}
+
+fn check_within_boundaries(r: &mut Reporter, parsed: &PolygonsFile) {
+    let boundaries: Vec<Polygon> = parsed
+        .boundaries
+        .iter()
+        .filter(|e| e.ring.len() >= 3)
+        .map(|e| Polygon::new(to_line_string(&e.ring), vec![]))
+        .collect();
+    if boundaries.is_empty() {
+        return;
+    }
+    for entry in &parsed.regions {
+        let total = entry.ring.len();
+        let outside = entry
+            .ring
+            .iter()
+            .filter(|p| {
+                let c = Coord { x: p[0], y: p[1] };
+                !boundaries.iter().any(|b| b.intersects(&c))
+            })
+            .count();
+        if total > 0 && outside == total {
+            r.region(
+                Severity::Warning,
+                entry,
+                "region lies entirely outside the location's boundaries".to_string(),
+            );
+        } else if outside > 0 {
+            r.region(
+                Severity::Warning,
+                entry,
+                format!(
+                    "{} of {} vertices fall outside the location's boundaries",
+                    outside, total
+                ),
+            );
+        }
+    }
+}
+
+fn check_pois(r: &mut Reporter, parsed: &PolygonsFile) {
+    let boundaries: Vec<Polygon> = parsed
+        .boundaries
+        .iter()
+        .filter(|e| e.ring.len() >= 3)
+        .map(|e| Polygon::new(to_line_string(&e.ring), vec![]))
+        .collect();
+    let mut collections: HashMap<&str, usize> = HashMap::new();
+    for collection in &parsed.pois {
+        *collections.entry(&collection.id).or_insert(0) += 1;
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for poi in &collection.pois {
+            *seen.entry(&poi.id).or_insert(0) += 1;
+            let c = Coord {
+                x: poi.coord[0],
+                y: poi.coord[1],
+            };
+            if !boundaries.is_empty() && !boundaries.iter().any(|b| b.intersects(&c)) {
+                r.push(
+                    Severity::Warning,
+                    line_of_id(r.raw, &poi.id),
+                    Some(&poi.id),
+                    format!(
+                        "POI in `{}` lies outside the location's boundaries",
+                        collection.id
+                    ),
+                );
+            }
+        }
+        for (id, count) in seen {
+            if count > 1 {
+                r.push(
+                    Severity::Error,
+                    line_of_id(r.raw, id),
+                    Some(id),
+                    format!("id is used by {} POIs in `{}`", count, collection.id),
+                );
+            }
+        }
+    }
+    for (id, count) in collections {
+        if count > 1 {
+            r.push(
+                Severity::Error,
+                line_of_id(r.raw, id),
+                Some(id),
+                format!("id is used by {} POI collections", count),
+            );
+        }
+    }
+}
+
+fn check_lines(r: &mut Reporter, parsed: &PolygonsFile) {
+    let mut collections: HashMap<&str, usize> = HashMap::new();
+    for collection in &parsed.lines {
+        *collections.entry(&collection.id).or_insert(0) += 1;
+        for (i, line) in collection.lines.iter().enumerate() {
+            if line.len() < 2 {
+                r.push(
+                    Severity::Error,
+                    line_of_id(r.raw, &collection.id),
+                    Some(&collection.id),
+                    format!(
+                        "line {} has {} point(s); a line needs at least 2",
+                        i,
+                        line.len()
+                    ),
+                );
+            }
+        }
+    }
+    for (id, count) in collections {
+        if count > 1 {
+            r.push(
+                Severity::Error,
+                line_of_id(r.raw, id),
+                Some(id),
+                format!("id is used by {} line collections", count),
+            );
+        }
+    }
+}
+
+fn to_line_string(ring: &[[f64; 2]]) -> LineString {
+    LineString::from(ring.iter().map(|p| (p[0], p[1])).collect::<Vec<_>>())
+}
+
+/// Line of the first `"id": "<id>"` member in the raw file, for pointing at a
+/// region. The same string elsewhere (a name, a category) is skipped.
+fn line_of_id(raw: &str, id: &str) -> Option<usize> {
+    let quoted = format!("\"{}\"", id);
+    let (offset, _) = raw.match_indices(&quoted).find(|(offset, _)| {
+        raw[..*offset]
+            .trim_end()
+            .strip_suffix(':')
+            .is_some_and(|key| key.trim_end().ends_with("\"id\""))
+    })?;
+    Some(raw[..offset].matches('\n').count() + 1)
+}
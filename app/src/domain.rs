@@ -1,2 +1,7 @@
+pub mod adjacency;
 pub mod geometry;
+pub mod lines;
 pub mod location;
+pub mod poi;
+pub mod region_index;
+pub mod simplify;
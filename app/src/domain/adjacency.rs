@@ -0,0 +1,120 @@
+use geo::{BoundingRect, Centroid, EuclideanDistance};
+
+use crate::domain::{
+    geometry::{Local, Polygon},
+    region_index::RegionIndex,
+};
+
+/// Regions this close count as touching. Simplified rings leave small gaps
+/// along borders that real regions share.
+pub const TOUCH_TOLERANCE_M: f64 = 20.0;
+
+/// Which regions of a location border which, by index into its regions.
+/// Built once when the location is read.
+pub struct Adjacency {
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl Adjacency {
+    /// Regions are neighbours when they share a border or come within
+    /// [`TOUCH_TOLERANCE_M`] of each other. Only regions whose boxes come
+    /// that close, found through `index`, are compared.
+    pub fn new(regions: &[Polygon<Local>], index: &RegionIndex) -> Self {
+        let mut neighbours = vec![vec![]; regions.len()];
+        for (i, region) in regions.iter().enumerate() {
+            let Some(rect) = region.inner.bounding_rect() else {
+                continue;
+            };
+            let x_bounds = [
+                rect.min().x - TOUCH_TOLERANCE_M,
+                rect.max().x + TOUCH_TOLERANCE_M,
+            ];
+            let y_bounds = [
+                rect.min().y - TOUCH_TOLERANCE_M,
+                rect.max().y + TOUCH_TOLERANCE_M,
+            ];
+            // Each pair once, from its lower index.
+            let later = index
+                .in_bounds(x_bounds, y_bounds)
+                .into_iter()
+                .filter(|&j| j > i);
+            for j in later {
+                if region.inner.euclidean_distance(&regions[j].inner) <= TOUCH_TOLERANCE_M {
+                    neighbours[i].push(j);
+                    neighbours[j].push(i);
+                }
+            }
+        }
+        Self { neighbours }
+    }
+
+    /// The regions bordering region `i`, in region order.
+    pub fn of(&self, i: usize) -> &[usize] {
+        self.neighbours.get(i).map_or(&[], |n| n.as_slice())
+    }
+
+    /// The mean value of region `i`'s neighbours, leaving out those without
+    /// one. `values` is indexed like the regions.
+    pub fn neighbour_mean(&self, i: usize, values: &[Option<f64>]) -> Option<f64> {
+        let known: Vec<f64> = self
+            .of(i)
+            .iter()
+            .filter_map(|&j| values.get(j).copied().flatten())
+            .collect();
+        (!known.is_empty()).then(|| known.iter().sum::<f64>() / known.len() as f64)
+    }
+
+    /// Moran's I over the regions with a value, each neighbour weighted
+    /// alike: towards 1 when neighbours have similar values, around 0 when
+    /// values are scattered at random, negative when they alternate. `None`
+    /// when no two valued regions border each other or every value is the
+    /// same.
+    pub fn morans_i(&self, values: &[Option<f64>]) -> Option<f64> {
+        let known: Vec<(usize, f64)> = values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| Some((i, (*v)?)))
+            .collect();
+        let n = known.len() as f64;
+        let mean = known.iter().map(|(_, v)| v).sum::<f64>() / n;
+        let variance: f64 = known.iter().map(|(_, v)| (v - mean).powi(2)).sum();
+        let mut weights = 0.0;
+        let mut covariance = 0.0;
+        for &(i, a) in &known {
+            for b in self
+                .of(i)
+                .iter()
+                .filter_map(|&j| values.get(j).copied().flatten())
+            {
+                weights += 1.0;
+                covariance += (a - mean) * (b - mean);
+            }
+        }
+        // Equal values can still leave a rounding-error variance.
+        let spread = known
+            .iter()
+            .any(|(_, v)| Some(*v) != known.first().map(|k| k.1));
+        (weights > 0.0 && spread).then(|| n / weights * covariance / variance)
+    }
+
+    /// The neighbour of region `i` lying most nearly in direction `(dx, dy)`
+    /// from it, centroid to centroid, if any lies within 90° of it.
+    pub fn towards(
+        &self,
+        regions: &[Polygon<Local>],
+        i: usize,
+        (dx, dy): (f64, f64),
+    ) -> Option<usize> {
+        let from = regions.get(i)?.inner.centroid()?;
+        self.of(i)
+            .iter()
+            .filter_map(|&j| {
+                let to = regions[j].inner.centroid()?;
+                let (x, y) = (to.x() - from.x(), to.y() - from.y());
+                let cos = (x * dx + y * dy) / x.hypot(y);
+                (cos > 0.0).then_some((j, cos))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(j, _)| j)
+    }
+}
@@ -1,5 +1,6 @@
 use geo::LineString as GeoLineString;
 use geo::Polygon as GeoPolygon;
+use geo::{Area, EuclideanLength, GeodesicArea, GeodesicLength};
 use std::marker::PhantomData;
 
 pub trait Projection {
@@ -9,16 +10,62 @@ pub trait Projection {
     // Note: in a terminal, Y is twice the size,
     // so it should probably be twice the amount
     const UNITS_PER_CELL_Y: f64;
+    // Whether x and y are metres, rather than degrees.
+    const METRES: bool;
+
+    /// `(lng, lat)` of `(x, y)` in a frame whose origin is at `origin`.
+    fn to_wgs84(x: f64, y: f64, origin: &Point<WGS84>) -> (f64, f64);
+
+    /// Length of a line, in metres.
+    fn length(line: &GeoLineString) -> f64;
+
+    /// Area of a polygon, in square metres.
+    fn area(polygon: &GeoPolygon) -> f64;
 }
+
+/// Metres in a degree of latitude, for the flat-earth step between a
+/// location's Local frame and WGS84. Close enough across a city.
+const METRES_PER_DEGREE: f64 = 111_320.0;
 pub struct Local;
 pub struct WGS84;
 impl Projection for Local {
     const UNITS_PER_CELL_X: f64 = 50.0;
     const UNITS_PER_CELL_Y: f64 = 100.0;
+    const METRES: bool = true;
+
+    fn to_wgs84(x: f64, y: f64, origin: &Point<WGS84>) -> (f64, f64) {
+        let metres_per_degree_lng = METRES_PER_DEGREE * origin.y.to_radians().cos();
+        (
+            origin.x + x / metres_per_degree_lng,
+            origin.y + y / METRES_PER_DEGREE,
+        )
+    }
+
+    fn length(line: &GeoLineString) -> f64 {
+        line.euclidean_length()
+    }
+
+    fn area(polygon: &GeoPolygon) -> f64 {
+        polygon.unsigned_area()
+    }
 }
 impl Projection for WGS84 {
     const UNITS_PER_CELL_X: f64 = 2.0;
     const UNITS_PER_CELL_Y: f64 = 4.0;
+    const METRES: bool = false;
+
+    fn to_wgs84(x: f64, y: f64, _origin: &Point<WGS84>) -> (f64, f64) {
+        (x, y)
+    }
+
+    // Along the ellipsoid, so long lines and big shapes stay right.
+    fn length(line: &GeoLineString) -> f64 {
+        line.geodesic_length()
+    }
+
+    fn area(polygon: &GeoPolygon) -> f64 {
+        polygon.geodesic_area_unsigned()
+    }
 }
 
 pub struct Point<P: Projection> {
@@ -0,0 +1,89 @@
+use geo::LineString;
+
+use crate::{
+    domain::geometry::{Local, Polyline},
+    theme::ThemeColor,
+};
+
+/// A named set of line features drawn alike, e.g. a rail line or the rivers
+/// of a city. Comes from the polygon file or a `lines` layer.
+pub struct LineCollection {
+    pub id: String,
+    pub name: String,
+    pub style: LineStyle,
+    pub lines: Vec<Polyline<Local>>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default)]
+pub struct LineStyle {
+    /// The theme's `polyline` colour if not given.
+    #[serde(default)]
+    pub color: Option<ThemeColor>,
+    #[serde(default)]
+    pub dash: Dash,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Dash {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Dash::Solid => "solid",
+            Dash::Dashed => "dashed",
+            Dash::Dotted => "dotted",
+        }
+    }
+}
+
+/// JSON shape for one line collection, in a polygon file's `lines` list or a
+/// `lines` layer's output.
+#[derive(serde::Deserialize)]
+pub struct LineCollectionEntry {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub style: LineStyle,
+    /// Each line is a list of `[x, y]` in local metres, like polygon rings.
+    pub lines: Vec<Vec<[f64; 2]>>,
+}
+
+impl LineCollection {
+    /// The collection in the JSON shape it is read from.
+    pub fn to_json(&self) -> serde_json::Value {
+        let lines: Vec<Vec<[f64; 2]>> = self
+            .lines
+            .iter()
+            .map(|l| l.inner.coords().map(|c| [c.x, c.y]).collect())
+            .collect();
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "style": self.style,
+            "lines": lines,
+        })
+    }
+}
+
+pub fn parse_line_collection(entry: LineCollectionEntry) -> LineCollection {
+    LineCollection {
+        id: entry.id,
+        name: entry.name,
+        style: entry.style,
+        lines: entry
+            .lines
+            .into_iter()
+            .map(|line| {
+                Polyline::new(LineString::from(
+                    line.into_iter().map(|p| (p[0], p[1])).collect::<Vec<_>>(),
+                ))
+            })
+            .collect(),
+    }
+}
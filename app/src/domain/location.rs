@@ -1,15 +1,42 @@
-use std::path::Path;
+use std::{
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use geo::LineString;
 use geo::Polygon as GeoPolygon;
 
-use crate::domain::geometry::{Local, Point, Polygon, RegionMetadata, WGS84};
+use crate::{
+    domain::{
+        adjacency::Adjacency,
+        geometry::{Local, Point, Polygon, RegionMetadata, WGS84},
+        lines::{parse_line_collection, LineCollection, LineCollectionEntry},
+        poi::{parse_poi_collection, PoiCollection, PoiCollectionEntry},
+        region_index::RegionIndex,
+        simplify::PolygonLevels,
+    },
+    error::DomainError,
+};
+
+/// Source of [`Location::generation`]s.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
 
 pub struct Location {
     pub tag: LocationTag,
     pub latlng: Point<WGS84>,
     pub boundaries: Vec<Polygon<Local>>,
     pub regions: Vec<Polygon<Local>>,
+    pub pois: Vec<PoiCollection>,
+    pub lines: Vec<LineCollection>,
+    /// Which regions border which.
+    pub adjacency: Adjacency,
+    /// Finds regions by where they are.
+    pub index: RegionIndex,
+    /// Boundaries and regions simplified for drawing zoomed out.
+    pub levels: PolygonLevels<Local>,
+    /// Different for every location read, including a reread of the same
+    /// one, so what is cached about its regions by index can't outlive them.
+    pub generation: u64,
 }
 
 pub struct LocationTag {
@@ -23,6 +50,45 @@ pub struct LocationTag {
     pub created_date: String,
 }
 
+impl LocationTag {
+    /// The record as it appears in `locations.json`, plus whether it is starred.
+    pub fn to_json(&self, starred: bool) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "coord": [self.coord.x, self.coord.y],
+            "countryCode": self.country_code,
+            "countrySubdivision": self.country_subdivision,
+            "type": self.kind,
+            "status": self.status,
+            "createdDate": self.created_date,
+            "starred": starred,
+        })
+    }
+}
+
+impl Location {
+    /// The record with every boundary and region attached, rings in local
+    /// metres as in the polygon file.
+    pub fn to_json(&self, starred: bool) -> serde_json::Value {
+        let mut out = self.tag.to_json(starred);
+        out["boundaries"] = self.boundaries.iter().map(polygon_json).collect();
+        out["regions"] = self.regions.iter().map(polygon_json).collect();
+        out
+    }
+}
+
+fn polygon_json(poly: &Polygon<Local>) -> serde_json::Value {
+    let ring: Vec<[f64; 2]> = poly.inner.exterior().coords().map(|c| [c.x, c.y]).collect();
+    serde_json::json!({
+        "id": poly.metadata.id,
+        "name": poly.metadata.name,
+        "kind": poly.metadata.kind,
+        "category": poly.metadata.category,
+        "ring": ring,
+    })
+}
+
 #[derive(serde::Deserialize)]
 pub struct LocationFile {
     pub id: String,
@@ -41,22 +107,26 @@ pub struct LocationFile {
 }
 
 #[derive(serde::Deserialize)]
-struct PolygonsFile {
-    boundaries: Vec<PolygonEntry>,
-    regions: Vec<PolygonEntry>,
+pub struct PolygonsFile {
+    pub boundaries: Vec<PolygonEntry>,
+    pub regions: Vec<PolygonEntry>,
+    #[serde(default)]
+    pub pois: Vec<PoiCollectionEntry>,
+    #[serde(default)]
+    pub lines: Vec<LineCollectionEntry>,
 }
 
 /// JSON shape for one polygon entry. Extra fields in the file are ignored.
 #[derive(serde::Deserialize)]
-struct PolygonEntry {
-    name: String,
-    id: String,
-    kind: Option<String>,
-    category: String,
-    ring: Vec<[f64; 2]>,
+pub struct PolygonEntry {
+    pub name: String,
+    pub id: String,
+    pub kind: Option<String>,
+    pub category: String,
+    pub ring: Vec<[f64; 2]>,
 }
 
-fn parse_polygon(entry: PolygonEntry) -> Polygon<Local> {
+pub fn parse_polygon(entry: PolygonEntry) -> Polygon<Local> {
     let exterior = LineString::from(
         entry
             .ring
@@ -76,18 +146,41 @@ fn parse_polygon(entry: PolygonEntry) -> Polygon<Local> {
 }
 
 impl LocationFile {
-    pub fn get_location(&self, base_path: &Path) -> Option<Location> {
+    pub fn get_location(&self, base_path: &Path) -> Result<Location, DomainError> {
         let latlng = Point::new(self.coord[0], self.coord[1]);
         let polygon_path = base_path.join(self.polygon_path.clone());
-        let raw_polygons = std::fs::read_to_string(polygon_path).ok()?;
-        let parsed: PolygonsFile = serde_json::from_str(&raw_polygons).ok()?;
-        let boundaries = parsed.boundaries.into_iter().map(parse_polygon).collect();
-        let regions = parsed.regions.into_iter().map(parse_polygon).collect();
-        Some(Location {
+        let raw_polygons =
+            std::fs::read_to_string(&polygon_path).map_err(|source| DomainError::ReadPolygons {
+                path: polygon_path.clone(),
+                source,
+            })?;
+        let parsed: PolygonsFile =
+            serde_json::from_str(&raw_polygons).map_err(|source| DomainError::ParsePolygons {
+                path: polygon_path.clone(),
+                source,
+            })?;
+        let boundaries: Vec<_> = parsed.boundaries.into_iter().map(parse_polygon).collect();
+        let regions: Vec<_> = parsed.regions.into_iter().map(parse_polygon).collect();
+        let index = RegionIndex::new(&regions);
+        let adjacency = Adjacency::new(&regions, &index);
+        let levels = PolygonLevels::new(&boundaries, &regions);
+        let pois = parsed.pois.into_iter().map(parse_poi_collection).collect();
+        let lines = parsed
+            .lines
+            .into_iter()
+            .map(parse_line_collection)
+            .collect();
+        Ok(Location {
             tag: self.get_location_tag(),
             latlng,
             boundaries,
             regions,
+            pois,
+            lines,
+            adjacency,
+            index,
+            levels,
+            generation: NEXT_GENERATION.fetch_add(1, Ordering::Relaxed),
         })
     }
     pub fn get_location_tag(&self) -> LocationTag {
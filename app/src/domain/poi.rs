@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use crate::domain::geometry::{Local, Point};
+
+/// A named set of points of interest sharing a category, e.g. the train
+/// stations of a city. Comes from the polygon file or a `pois` layer.
+pub struct PoiCollection {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub pois: Vec<Poi>,
+}
+
+pub struct Poi {
+    pub id: String,
+    pub name: String,
+    pub point: Point<Local>,
+    /// Anything else known about the POI, shown as given.
+    pub attributes: BTreeMap<String, serde_json::Value>,
+}
+
+/// JSON shape for one POI collection, in a polygon file's `pois` list or a
+/// `pois` layer's output.
+#[derive(serde::Deserialize)]
+pub struct PoiCollectionEntry {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub pois: Vec<PoiEntry>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct PoiEntry {
+    pub id: String,
+    pub name: String,
+    /// Local metres, like polygon rings.
+    pub coord: [f64; 2],
+    #[serde(default)]
+    pub attributes: BTreeMap<String, serde_json::Value>,
+}
+
+impl PoiCollection {
+    /// The collection in the JSON shape it is read from.
+    pub fn to_json(&self) -> serde_json::Value {
+        let pois: Vec<serde_json::Value> = self
+            .pois
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "id": p.id,
+                    "name": p.name,
+                    "coord": [p.point.x, p.point.y],
+                    "attributes": p.attributes,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "category": self.category,
+            "pois": pois,
+        })
+    }
+}
+
+pub fn parse_poi_collection(entry: PoiCollectionEntry) -> PoiCollection {
+    PoiCollection {
+        id: entry.id,
+        name: entry.name,
+        category: entry.category,
+        pois: entry
+            .pois
+            .into_iter()
+            .map(|p| Poi {
+                id: p.id,
+                name: p.name,
+                point: Point::new(p.coord[0], p.coord[1]),
+                attributes: p.attributes,
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,80 @@
+use geo::{BoundingRect, Coord, EuclideanDistance, Intersects};
+use rstar::{
+    primitives::{GeomWithData, Rectangle},
+    RTree, AABB,
+};
+
+use crate::domain::geometry::{Polygon, Projection};
+
+type Entry = GeomWithData<Rectangle<[f64; 2]>, usize>;
+
+/// An R-tree over region bounding boxes, by index into the regions it was
+/// built from, so a location with thousands of regions only looks at those
+/// near a given area.
+pub struct RegionIndex {
+    tree: RTree<Entry>,
+}
+
+impl RegionIndex {
+    pub fn new<P: Projection>(regions: &[Polygon<P>]) -> Self {
+        let entries = regions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| {
+                let rect = r.inner.bounding_rect()?;
+                let corners = Rectangle::from_corners(rect.min().into(), rect.max().into());
+                Some(GeomWithData::new(corners, i))
+            })
+            .collect();
+        Self {
+            tree: RTree::bulk_load(entries),
+        }
+    }
+
+    /// Regions whose bounding box overlaps the given bounds, in region order
+    /// so they draw in the same order as without the index.
+    pub fn in_bounds(&self, x_bounds: [f64; 2], y_bounds: [f64; 2]) -> Vec<usize> {
+        let envelope = AABB::from_corners([x_bounds[0], y_bounds[0]], [x_bounds[1], y_bounds[1]]);
+        let mut found: Vec<usize> = self
+            .tree
+            .locate_in_envelope_intersecting(&envelope)
+            .map(|e| e.data)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    /// The first region, in region order, that `(x, y)` is inside or on the
+    /// edge of.
+    pub fn at<P: Projection>(&self, regions: &[Polygon<P>], x: f64, y: f64) -> Option<usize> {
+        let coord = Coord { x, y };
+        self.tree
+            .locate_all_at_point(&[x, y])
+            .map(|e| e.data)
+            .filter(|&i| regions[i].inner.intersects(&coord))
+            .min()
+    }
+
+    /// The region closest to `(x, y)` and how far away it is, 0 when inside.
+    pub fn nearest<P: Projection>(
+        &self,
+        regions: &[Polygon<P>],
+        x: f64,
+        y: f64,
+    ) -> Option<(usize, f64)> {
+        let point = geo::Point::new(x, y);
+        let mut best: Option<(usize, f64)> = None;
+        // Boxes come nearest first, and a region is never closer than its
+        // box, so stop once the next box is further than the best region.
+        for (entry, box_distance_2) in self.tree.nearest_neighbor_iter_with_distance_2(&[x, y]) {
+            if best.is_some_and(|(_, d)| box_distance_2 > d * d) {
+                break;
+            }
+            let distance = regions[entry.data].inner.euclidean_distance(&point);
+            if best.is_none_or(|(_, d)| distance < d) {
+                best = Some((entry.data, distance));
+            }
+        }
+        best
+    }
+}
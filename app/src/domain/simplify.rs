@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+
+use geo::{BoundingRect, Coord, LineString, Polygon as GeoPolygon, Simplify};
+
+use crate::domain::geometry::{Polygon, Polyline, Projection};
+
+/// Tolerances of the simplified levels, as fractions of the shapes' extent.
+/// The coarsest is about right for a whole city drawn in 40 cells.
+const LEVELS: [f64; 4] = [1.0 / 6400.0, 1.0 / 1600.0, 1.0 / 400.0, 1.0 / 100.0];
+
+/// Boundaries and regions simplified to within `tolerance` world units,
+/// indexed like the originals.
+pub struct PolygonLevel<P: Projection> {
+    pub tolerance: f64,
+    pub boundaries: Vec<Polygon<P>>,
+    pub regions: Vec<Polygon<P>>,
+}
+
+/// Copies of a location's boundaries and regions at coarser and coarser
+/// tolerances, for drawing zoomed out. Rings are split into arcs wherever
+/// three or more edges meet, and each arc is simplified on its own, so a
+/// border shared by two regions simplifies the same way in both and no gaps
+/// or overlaps open up between them.
+pub struct PolygonLevels<P: Projection> {
+    /// Finest first.
+    levels: Vec<PolygonLevel<P>>,
+}
+
+impl<P: Projection> PolygonLevels<P> {
+    pub fn new(boundaries: &[Polygon<P>], regions: &[Polygon<P>]) -> Self {
+        let all = || boundaries.iter().chain(regions);
+        let junctions = junctions(all().flat_map(|p| rings(&p.inner)));
+        let extent = extent(all().map(|p| p.inner.exterior()));
+        let mut levels: Vec<PolygonLevel<P>> = vec![];
+        for fraction in LEVELS {
+            let tolerance = extent * fraction;
+            // A ring too small to survive a level keeps its shape from the
+            // level before.
+            let simplify = |originals: &[Polygon<P>], finer: Option<&[Polygon<P>]>| {
+                originals
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        let fallback = finer.map_or(&p.inner, |f| &f[i].inner);
+                        let inner = simplify_polygon(&p.inner, &junctions, tolerance)
+                            .unwrap_or_else(|| fallback.clone());
+                        Polygon::new(p.metadata.clone(), inner)
+                    })
+                    .collect()
+            };
+            let finer = levels.last();
+            let level = PolygonLevel {
+                tolerance,
+                boundaries: simplify(boundaries, finer.map(|l| l.boundaries.as_slice())),
+                regions: simplify(regions, finer.map(|l| l.regions.as_slice())),
+            };
+            levels.push(level);
+        }
+        Self { levels }
+    }
+
+    /// The coarsest level within `tolerance`, or `None` when only the
+    /// originals are detailed enough.
+    pub fn at(&self, tolerance: f64) -> Option<&PolygonLevel<P>> {
+        self.levels.iter().rev().find(|l| l.tolerance <= tolerance)
+    }
+}
+
+/// Lines such as the coastlines, in full and at coarser and coarser
+/// tolerances. Lines are simplified one at a time; their ends never move,
+/// so lines that meet still meet.
+pub struct LineLevels<P: Projection> {
+    full: Vec<Polyline<P>>,
+    /// Tolerance and lines, finest first.
+    levels: Vec<(f64, Vec<Polyline<P>>)>,
+}
+
+impl<P: Projection> LineLevels<P> {
+    pub fn new(full: Vec<Polyline<P>>) -> Self {
+        let extent = extent(full.iter().map(|l| &l.inner));
+        let levels = LEVELS
+            .iter()
+            .map(|fraction| {
+                let tolerance = extent * fraction;
+                let lines = full
+                    .iter()
+                    .map(|l| Polyline::new(l.inner.simplify(&tolerance)))
+                    .collect();
+                (tolerance, lines)
+            })
+            .collect();
+        Self { full, levels }
+    }
+
+    pub fn full(&self) -> &[Polyline<P>] {
+        &self.full
+    }
+
+    /// The coarsest lines within `tolerance`.
+    pub fn at(&self, tolerance: f64) -> &[Polyline<P>] {
+        self.levels
+            .iter()
+            .rev()
+            .find(|(t, _)| *t <= tolerance)
+            .map_or(&self.full, |(_, lines)| lines)
+    }
+}
+
+/// Coordinates compared exactly, as shared vertices are written the same
+/// in every ring they belong to.
+type Key = (u64, u64);
+
+fn key(c: &Coord) -> Key {
+    (c.x.to_bits(), c.y.to_bits())
+}
+
+fn rings(polygon: &GeoPolygon) -> impl Iterator<Item = &LineString> {
+    std::iter::once(polygon.exterior()).chain(polygon.interiors())
+}
+
+/// Width or height of the box around every line, whichever is larger.
+fn extent<'a>(lines: impl Iterator<Item = &'a LineString>) -> f64 {
+    lines
+        .filter_map(|l| l.bounding_rect())
+        .reduce(|a, b| {
+            geo::Rect::new(
+                Coord {
+                    x: a.min().x.min(b.min().x),
+                    y: a.min().y.min(b.min().y),
+                },
+                Coord {
+                    x: a.max().x.max(b.max().x),
+                    y: a.max().y.max(b.max().y),
+                },
+            )
+        })
+        .map_or(0.0, |r| r.width().max(r.height()))
+}
+
+/// Vertices where arcs must end: those joined to one other vertex, or to
+/// three or more, across every ring. A vertex inside a shared border is
+/// joined to the same two vertices in both rings, so it isn't one.
+fn junctions<'a>(rings: impl Iterator<Item = &'a LineString>) -> HashSet<Key> {
+    let mut joined: HashMap<Key, HashSet<Key>> = HashMap::new();
+    for ring in rings {
+        for line in ring.lines() {
+            if line.start == line.end {
+                continue;
+            }
+            let (a, b) = (key(&line.start), key(&line.end));
+            joined.entry(a).or_default().insert(b);
+            joined.entry(b).or_default().insert(a);
+        }
+    }
+    joined
+        .into_iter()
+        .filter(|(_, others)| others.len() != 2)
+        .map(|(k, _)| k)
+        .collect()
+}
+
+/// `None` if a ring would be left with fewer than three corners.
+fn simplify_polygon(
+    polygon: &GeoPolygon,
+    junctions: &HashSet<Key>,
+    tolerance: f64,
+) -> Option<GeoPolygon> {
+    let exterior = simplify_ring(polygon.exterior(), junctions, tolerance)?;
+    let interiors = polygon
+        .interiors()
+        .iter()
+        .map(|ring| simplify_ring(ring, junctions, tolerance))
+        .collect::<Option<Vec<_>>>()?;
+    Some(GeoPolygon::new(exterior, interiors))
+}
+
+fn simplify_ring(
+    ring: &LineString,
+    junctions: &HashSet<Key>,
+    tolerance: f64,
+) -> Option<LineString> {
+    // Without the closing repeat of the first vertex.
+    let coords = &ring.0[..ring.0.len().saturating_sub(1)];
+    let n = coords.len();
+    if n < 3 {
+        return None;
+    }
+    let mut ends: Vec<usize> = (0..n)
+        .filter(|&i| junctions.contains(&key(&coords[i])))
+        .collect();
+    // A ring touching no other is one closed arc. Starting it at its
+    // lowest vertex keeps it the same however the ring is written.
+    if ends.is_empty() {
+        ends.push((0..n).min_by(|&a, &b| cmp(&coords[a], &coords[b]))?);
+    }
+    let mut out = vec![];
+    for (k, &start) in ends.iter().enumerate() {
+        let end = ends.get(k + 1).copied().unwrap_or(ends[0] + n);
+        let arc: Vec<Coord> = (start..=end).map(|i| coords[i % n]).collect();
+        let simplified = simplify_arc(arc, tolerance);
+        out.extend_from_slice(&simplified[..simplified.len() - 1]);
+    }
+    if out.len() < 3 {
+        return None;
+    }
+    out.push(out[0]);
+    Some(LineString(out))
+}
+
+/// Neighbouring rings walk a shared arc in opposite directions, so every arc
+/// is simplified in one fixed direction to come out the same from both.
+fn simplify_arc(mut arc: Vec<Coord>, tolerance: f64) -> Vec<Coord> {
+    let last = arc.len() - 1;
+    let reversed = match cmp(&arc[0], &arc[last]) {
+        std::cmp::Ordering::Equal => last > 1 && cmp(&arc[1], &arc[last - 1]).is_gt(),
+        ordering => ordering.is_gt(),
+    };
+    if reversed {
+        arc.reverse();
+    }
+    let mut simplified = LineString(arc).simplify(&tolerance).0;
+    if reversed {
+        simplified.reverse();
+    }
+    simplified
+}
+
+fn cmp(a: &Coord, b: &Coord) -> std::cmp::Ordering {
+    a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+}
@@ -0,0 +1,205 @@
+use std::{fmt, io, path::PathBuf, process::ExitStatus};
+
+use crate::config::validation::ConfigProblem;
+
+/// Failures opening the dataset or fetching a location from it.
+#[derive(Debug, thiserror::Error)]
+pub enum DbError {
+    #[error("could not read location index {}", path.display())]
+    ReadIndex {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("could not parse location index {}", path.display())]
+    ParseIndex {
+        path: PathBuf,
+        #[source]
+        source: simd_json::Error,
+    },
+    #[error("no location with id '{0}'")]
+    NotFound(String),
+    #[error("could not load location '{id}'")]
+    Location {
+        id: String,
+        #[source]
+        source: DomainError,
+    },
+}
+
+/// Failures turning a location's files into domain types.
+#[derive(Debug, thiserror::Error)]
+pub enum DomainError {
+    #[error("could not read polygon file {}", path.display())]
+    ReadPolygons {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("could not parse polygon file {}", path.display())]
+    ParsePolygons {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("could not read config {}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("could not parse config {}", path.display())]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("no [profile.{name}] in config (available: {})", if available.is_empty() { "none".to_string() } else { available.join(", ") })]
+    UnknownProfile {
+        name: String,
+        available: Vec<String>,
+    },
+    #[error("config {} extends too many files; is there a cycle?", path.display())]
+    ExtendsTooDeep { path: PathBuf },
+    #[error("{} problem(s) in config:{}", problems.len(), problems.iter().map(|p| format!("\n  {}", p)).collect::<String>())]
+    Invalid { problems: Vec<ConfigProblem> },
+}
+
+/// Failures running a layer command. Each variant names the command so the
+/// message is useful on its own in the Layers list.
+#[derive(Debug, thiserror::Error)]
+pub enum LayerError {
+    #[error("could not start `{command}`")]
+    Spawn {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("could not talk to `{command}`")]
+    Io {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("`{command}` timed out after {secs}s")]
+    Timeout { command: String, secs: u64 },
+    #[error("`{command}` exited with {status}: {stderr}")]
+    Failed {
+        command: String,
+        status: ExitStatus,
+        stderr: String,
+    },
+    #[error("`{command}` printed output that is not a JSON object of numbers")]
+    Parse {
+        command: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("`{command}` printed output that is not a JSON list of POI collections")]
+    ParsePois {
+        command: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("`{command}` printed output that is not a JSON list of line collections")]
+    ParseLines {
+        command: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("layer `{layer}`, which this one averages, did not load")]
+    Source { layer: String },
+}
+
+/// Failures writing the state file.
+#[derive(Debug, thiserror::Error)]
+pub enum StateError {
+    #[error("could not determine the XDG data directory")]
+    NoDataDir,
+    #[error("could not save state to {}", path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Ids in a session, from the state file or startup flags, that match nothing.
+/// They are skipped and the rest of the session is restored.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("layer '{0}' is not configured")]
+    UnknownLayer(String),
+    #[error("region '{region}' is not in location '{location}'")]
+    UnknownRegion { region: String, location: String },
+}
+
+/// A map or region export that could not be written.
+#[derive(Debug, thiserror::Error)]
+#[error("could not export {}", path.display())]
+pub struct ExportError {
+    pub path: PathBuf,
+    #[source]
+    pub source: Box<dyn std::error::Error>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClipboardError {
+    #[error("no system clipboard")]
+    Native(#[source] arboard::Error),
+    #[error("could not send OSC 52 to the terminal")]
+    Osc52(#[source] io::Error),
+}
+
+/// An error flattened for display: the top-level message followed by each
+/// `source()` beneath it. Cheap to clone and hold in the `Model`.
+#[derive(Clone)]
+pub struct ErrorReport {
+    pub lines: Vec<String>,
+    /// What the user can do about it, shown apart from the cause chain.
+    pub hint: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn new(err: &(dyn std::error::Error + 'static)) -> Self {
+        let mut lines = vec![err.to_string()];
+        let mut source = err.source();
+        while let Some(e) = source {
+            lines.push(e.to_string());
+            source = e.source();
+        }
+        Self { lines, hint: None }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// The whole chain on one line, for places without room for more.
+    /// Multi-line messages (like a list of config problems) are flattened.
+    pub fn one_line(&self) -> String {
+        self.lines
+            .iter()
+            .map(|l| l.lines().map(str::trim).collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join(": ")
+    }
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = self.lines.iter();
+        if let Some(first) = lines.next() {
+            write!(f, "{}", first)?;
+        }
+        for line in lines {
+            write!(f, "\n  caused by: {}", line)?;
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,13 @@
 use std::time::Duration;
 
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 
 use crate::message::Message;
 
 pub fn poll_and_handle_event() -> Result<Option<Message>, Box<dyn std::error::Error>> {
     if event::poll(Duration::from_millis(250))? {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Press {
+        match event::read()? {
+            Event::Key(key) if key.kind == event::KeyEventKind::Press => {
                 return Ok(match (key.code, key.modifiers) {
                     (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(Message::Quit),
                     (KeyCode::Char(c), _) => Some(Message::Char(c)),
@@ -22,6 +22,19 @@ pub fn poll_and_handle_event() -> Result<Option<Message>, Box<dyn std::error::Er
                     _ => None,
                 });
             }
+            Event::Mouse(mouse) => {
+                return Ok(match mouse.kind {
+                    MouseEventKind::Moved
+                    | MouseEventKind::Down(MouseButton::Left)
+                    | MouseEventKind::Drag(MouseButton::Left) => Some(Message::Mouse {
+                        column: mouse.column,
+                        row: mouse.row,
+                        click: mouse.kind == MouseEventKind::Down(MouseButton::Left),
+                    }),
+                    _ => None,
+                });
+            }
+            _ => (),
         }
     }
     Ok(None)
@@ -0,0 +1,103 @@
+pub mod png;
+pub mod regions;
+pub mod svg;
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use crate::{
+    components::map_view::{MapView, MapViewCtx},
+    domain::geometry::Projection,
+    error::{ErrorReport, ExportError},
+    message::Message,
+    update::Update,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    Svg,
+    Png,
+}
+
+impl MapFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MapFormat::Svg => "svg",
+            MapFormat::Png => "png",
+        }
+    }
+}
+
+/// An export with everything it needs copied out of the screen, so it can be
+/// written on a background thread while the UI carries on.
+pub enum ExportJob {
+    /// A map already drawn as SVG; PNGs are rasterised on the worker.
+    Map { svg: String, format: MapFormat },
+}
+
+impl ExportJob {
+    fn write(self, path: &Path) -> Result<(), Box<dyn Error>> {
+        match self {
+            ExportJob::Map { svg, format } => std::fs::write(path, encode_map(svg, format)?)?,
+        }
+        Ok(())
+    }
+}
+
+/// Draw the map exactly as it was last drawn on screen: same area, bounds,
+/// fills and overlays.
+pub fn map_svg<P: Projection + 'static>(
+    map: &MapView<P>,
+    ctx: &MapViewCtx<P>,
+) -> Result<String, Box<dyn Error>> {
+    let area = map.last_area();
+    if area.width < 3 || area.height < 3 {
+        return Err("the map has not been drawn yet".into());
+    }
+    Ok(svg::map_svg(map, ctx, area))
+}
+
+pub fn encode_map(svg: String, format: MapFormat) -> Result<Vec<u8>, Box<dyn Error>> {
+    match format {
+        MapFormat::Svg => Ok(svg.into_bytes()),
+        MapFormat::Png => png::svg_to_png(&svg),
+    }
+}
+
+/// Hand a drawn map to the export worker, or report why it could not be drawn.
+pub fn export_map(
+    path: PathBuf,
+    svg: Result<String, Box<dyn Error>>,
+    format: MapFormat,
+) -> (Vec<Update>, Vec<Message>) {
+    match svg {
+        Ok(svg) => (
+            vec![],
+            vec![Message::Export {
+                path,
+                job: ExportJob::Map { svg, format },
+            }],
+        ),
+        Err(source) => (vec![report(path, Err(source))], vec![]),
+    }
+}
+
+/// Turn the outcome of writing `path` into a toast or an error popup.
+pub fn report(path: PathBuf, result: Result<(), Box<dyn Error>>) -> Update {
+    match result {
+        Ok(()) => Update::Toast(format!("Saved {}", path.display())),
+        Err(source) => Update::SetError(ErrorReport::new(&ExportError { path, source })),
+    }
+}
+
+/// Write `job` to `path` on a background thread, reporting the outcome as a
+/// toast or an error.
+pub fn spawn_export(path: PathBuf, job: ExportJob, tx: mpsc::Sender<Update>) {
+    std::thread::spawn(move || {
+        let result = job.write(&path);
+        let _ = tx.send(report(path, result));
+    });
+}
@@ -0,0 +1,43 @@
+use std::{
+    error::Error,
+    sync::{Arc, OnceLock},
+};
+
+use resvg::{tiny_skia, usvg};
+
+/// Device pixels per SVG pixel, so text and thin outlines stay crisp in slides.
+const PNG_SCALE: f32 = 2.0;
+
+/// The system's fonts, scanned on the first PNG export and shared after that.
+fn system_fonts() -> Arc<usvg::fontdb::Database> {
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// Rasterise an SVG document. Labels use whatever system fonts are installed.
+pub fn svg_to_png(svg: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let options = usvg::Options {
+        fontdb: system_fonts(),
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(PNG_SCALE)
+        .ok_or("map is too large to rasterise")?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or("map is too large to rasterise")?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(PNG_SCALE, PNG_SCALE),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap.encode_png()?)
+}
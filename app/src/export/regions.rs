@@ -0,0 +1,204 @@
+use std::{collections::HashMap, error::Error, fs::File, path::Path, sync::Arc};
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use geo::{Coord, MapCoords};
+use geojson::{feature::Id, Feature, FeatureCollection, Geometry, JsonObject, JsonValue};
+use parquet::arrow::ArrowWriter;
+
+use crate::domain::{
+    geometry::{Local, Point, Polygon, Projection, WGS84},
+    location::Location,
+};
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum RegionFormat {
+    Csv,
+    /// A FeatureCollection with region polygons as geometry
+    Geojson,
+    Parquet,
+}
+
+impl RegionFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RegionFormat::Csv => "csv",
+            RegionFormat::Geojson => "geojson",
+            RegionFormat::Parquet => "parquet",
+        }
+    }
+
+    /// Pick the format from a file's extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" => Some(RegionFormat::Csv),
+            "geojson" | "json" => Some(RegionFormat::Geojson),
+            "parquet" | "pq" => Some(RegionFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+/// One layer's values, exported as a column named after the layer id.
+pub struct LayerColumn<'a> {
+    pub id: &'a str,
+    pub values: &'a HashMap<String, f64>,
+}
+
+/// `RegionMetadata` fields, in column order.
+const METADATA_COLUMNS: [&str; 4] = ["id", "name", "kind", "category"];
+
+/// Write one row per region: its metadata followed by a column per layer.
+/// Regions a layer has no value for get an empty cell (null in GeoJSON and
+/// Parquet).
+pub fn write_regions(
+    path: &Path,
+    location: &Location,
+    layers: &[LayerColumn],
+    format: RegionFormat,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        RegionFormat::Csv => write_csv(path, &location.regions, layers),
+        RegionFormat::Geojson => write_geojson(path, location, layers),
+        RegionFormat::Parquet => write_parquet(path, &location.regions, layers),
+    }
+}
+
+fn metadata_row(region: &Polygon<Local>) -> [Option<&str>; 4] {
+    let m = &region.metadata;
+    [
+        Some(&m.id),
+        Some(&m.name),
+        m.kind.as_deref(),
+        Some(&m.category),
+    ]
+}
+
+fn write_csv(
+    path: &Path,
+    regions: &[Polygon<Local>],
+    layers: &[LayerColumn],
+) -> Result<(), Box<dyn Error>> {
+    let mut w = csv::Writer::from_path(path)?;
+    write_table(&mut w, regions, layers)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// The same table as the CSV export, tab-separated so it pastes into a
+/// spreadsheet as cells.
+pub fn regions_tsv(regions: &[Polygon<Local>], layers: &[LayerColumn]) -> String {
+    let mut w = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .from_writer(vec![]);
+    // Writing into memory can't fail.
+    let _ = write_table(&mut w, regions, layers);
+    let bytes = w.into_inner().unwrap_or_default();
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+fn write_table<W: std::io::Write>(
+    w: &mut csv::Writer<W>,
+    regions: &[Polygon<Local>],
+    layers: &[LayerColumn],
+) -> csv::Result<()> {
+    w.write_record(
+        METADATA_COLUMNS
+            .iter()
+            .copied()
+            .chain(layers.iter().map(|l| l.id)),
+    )?;
+    for region in regions {
+        let values = layers.iter().map(|l| {
+            l.values
+                .get(&region.metadata.id)
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        });
+        let mut record: Vec<String> = metadata_row(region)
+            .map(|v| v.unwrap_or_default().to_string())
+            .to_vec();
+        record.extend(values);
+        w.write_record(&record)?;
+    }
+    Ok(())
+}
+
+fn write_geojson(
+    path: &Path,
+    location: &Location,
+    layers: &[LayerColumn],
+) -> Result<(), Box<dyn Error>> {
+    let features = location
+        .regions
+        .iter()
+        .map(|r| region_feature(r, &location.latlng, layers))
+        .collect();
+    let collection = FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&collection)?)?;
+    Ok(())
+}
+
+/// A region as a GeoJSON Feature: its ring as geometry, metadata and layer
+/// values as properties. GeoJSON coordinates are WGS84 longitude and
+/// latitude, so the ring is taken out of the Local frame around `origin`.
+pub fn region_feature(
+    region: &Polygon<Local>,
+    origin: &Point<WGS84>,
+    layers: &[LayerColumn],
+) -> Feature {
+    let mut properties = JsonObject::new();
+    for (key, value) in METADATA_COLUMNS.iter().zip(metadata_row(region)) {
+        properties.insert(key.to_string(), JsonValue::from(value));
+    }
+    for layer in layers {
+        let value = layer
+            .values
+            .get(&region.metadata.id)
+            .map_or(JsonValue::Null, |v| JsonValue::from(*v));
+        properties.insert(layer.id.to_string(), value);
+    }
+    let lng_lat = region.inner.map_coords(|c| {
+        let (x, y) = Local::to_wgs84(c.x, c.y, origin);
+        Coord { x, y }
+    });
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new((&lng_lat).into())),
+        id: Some(Id::String(region.metadata.id.clone())),
+        properties: Some(properties),
+        foreign_members: None,
+    }
+}
+
+fn write_parquet(
+    path: &Path,
+    regions: &[Polygon<Local>],
+    layers: &[LayerColumn],
+) -> Result<(), Box<dyn Error>> {
+    let mut fields = vec![];
+    let mut columns: Vec<ArrayRef> = vec![];
+    for (i, name) in METADATA_COLUMNS.iter().enumerate() {
+        fields.push(Field::new(*name, DataType::Utf8, *name == "kind"));
+        let values = regions.iter().map(|r| metadata_row(r)[i]);
+        columns.push(Arc::new(StringArray::from_iter(values)));
+    }
+    for layer in layers {
+        fields.push(Field::new(layer.id, DataType::Float64, true));
+        let values = regions
+            .iter()
+            .map(|r| layer.values.get(&r.metadata.id).copied());
+        columns.push(Arc::new(Float64Array::from_iter(values)));
+    }
+
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use std::fmt::Write;
+
+use geo::{Coord, InteriorPoint};
+use itertools::Itertools;
+use ratatui::{layout::Rect, style::Color};
+
+use crate::{
+    color::{hex, rgb},
+    components::map_view::{clip_line, dash_pattern, MapView, MapViewCtx},
+    domain::{
+        geometry::{Polygon, Projection},
+        simplify::LineLevels,
+    },
+};
+
+/// SVG pixels per terminal cell. Cells are roughly twice as tall as wide.
+const CELL_W: f64 = 8.0;
+const CELL_H: f64 = 16.0;
+/// For colours with no fixed RGB, i.e. the terminal's default.
+const FOREGROUND: &str = "#d0d0d0";
+
+/// Maps world coordinates onto the SVG canvas the same way ratatui's canvas
+/// maps them onto the terminal: bounds stretched over the block's inner area.
+struct Frame {
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+    fn px(&self, x: f64, y: f64) -> (f64, f64) {
+        let sx = (x - self.x_bounds[0]) / (self.x_bounds[1] - self.x_bounds[0]) * self.width;
+        let sy = (self.y_bounds[1] - y) / (self.y_bounds[1] - self.y_bounds[0]) * self.height;
+        (sx, sy)
+    }
+
+    fn overlaps<P: Projection>(&self, poly: &Polygon<P>) -> bool {
+        let coords = || poly.inner.exterior().coords();
+        let min_x = coords().map(|c| c.x).fold(f64::INFINITY, f64::min);
+        let max_x = coords().map(|c| c.x).fold(f64::NEG_INFINITY, f64::max);
+        let min_y = coords().map(|c| c.y).fold(f64::INFINITY, f64::min);
+        let max_y = coords().map(|c| c.y).fold(f64::NEG_INFINITY, f64::max);
+        max_x >= self.x_bounds[0]
+            && min_x <= self.x_bounds[1]
+            && max_y >= self.y_bounds[0]
+            && min_y <= self.y_bounds[1]
+    }
+
+    fn contains(&self, c: Coord) -> bool {
+        (self.x_bounds[0]..=self.x_bounds[1]).contains(&c.x)
+            && (self.y_bounds[0]..=self.y_bounds[1]).contains(&c.y)
+    }
+
+    fn ring_path<'a>(&self, coords: impl Iterator<Item = &'a Coord>) -> String {
+        let mut d = String::new();
+        for (i, c) in coords.enumerate() {
+            let (x, y) = self.px(c.x, c.y);
+            let _ = write!(d, "{}{:.1} {:.1} ", if i == 0 { "M" } else { "L" }, x, y);
+        }
+        d.push('Z');
+        d
+    }
+
+    /// Polyline path through only the in-view parts of a line, split wherever
+    /// it leaves the viewport. Keeps world-spanning coastlines small.
+    fn clipped_path<'a>(&self, coords: impl Iterator<Item = &'a Coord>) -> String {
+        let mut d = String::new();
+        let mut last_end: Option<(f64, f64)> = None;
+        for (a, b) in coords.tuple_windows() {
+            let Some([x1, y1, x2, y2]) = clip_line(a, b, self.x_bounds, self.y_bounds) else {
+                last_end = None;
+                continue;
+            };
+            let start = self.px(x1, y1);
+            let end = self.px(x2, y2);
+            if last_end != Some((x1, y1)) {
+                let _ = write!(d, "M{:.1} {:.1} ", start.0, start.1);
+            }
+            let _ = write!(d, "L{:.1} {:.1} ", end.0, end.1);
+            last_end = Some((x2, y2));
+        }
+        d
+    }
+}
+
+fn color(c: Color) -> String {
+    rgb(c).map(hex).unwrap_or_else(|| FOREGROUND.to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Vector version of `MapView::render` for the same area: regions with their
+/// layer fills, boundaries, coastline background, points, POI markers, the
+/// location marker, region labels and a legend for the active layer.
+pub fn map_svg<P: Projection + 'static>(
+    map: &MapView<P>,
+    ctx: &MapViewCtx<P>,
+    area: Rect,
+) -> String {
+    let (x_bounds, y_bounds) = map.bounds(area, ctx.center);
+    // The canvas draws inside the block border.
+    let inner_w = area.width.saturating_sub(2).max(1) as f64;
+    let inner_h = area.height.saturating_sub(2).max(1) as f64;
+    let frame = Frame {
+        x_bounds,
+        y_bounds,
+        width: inner_w * CELL_W,
+        height: inner_h * CELL_H,
+    };
+    let max_fill_value = ctx.fill_info.as_ref().and_then(|fi| fi.max_value());
+    let theme = &ctx.theme;
+    let background = color(theme.background);
+    let foreground = color(theme.foreground);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#,
+        w = frame.width,
+        h = frame.height
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        background
+    );
+
+    out.push_str("<g fill=\"none\" stroke-width=\"1\">\n");
+    for line in map.background.map_or(&[][..], LineLevels::full) {
+        let d = frame.clipped_path(line.inner.coords());
+        if !d.is_empty() {
+            let _ = writeln!(
+                out,
+                r#"<path d="{}" stroke="{}"/>"#,
+                d,
+                color(theme.coastline)
+            );
+        }
+    }
+    for poly in ctx.boundaries.iter().filter(|p| frame.overlaps(p)) {
+        let d = frame.ring_path(poly.inner.exterior().coords());
+        let _ = writeln!(
+            out,
+            r#"<path d="{}" stroke="{}"/>"#,
+            d,
+            color(theme.boundary)
+        );
+    }
+    out.push_str("</g>\n");
+
+    out.push_str("<g stroke-width=\"1\">\n");
+    for (i, poly) in ctx.regions.iter().enumerate() {
+        if !frame.overlaps(poly) {
+            continue;
+        }
+        let d = frame.ring_path(poly.inner.exterior().coords());
+        let selected = ctx.selected_region == &Some(i);
+        let fill = ctx
+            .fill_info
+            .as_ref()
+            .and_then(|fi| fi.color_for(&poly.metadata.id, max_fill_value));
+        let (fill, stroke) = match (selected, fill) {
+            (true, _) => (color(theme.selection), color(theme.selection)),
+            (false, Some(c)) => (color(c), color(c)),
+            (false, None) => ("none".to_string(), color(theme.region)),
+        };
+        let _ = writeln!(
+            out,
+            r#"<path d="{}" fill="{}" stroke="{}"><title>{}</title></path>"#,
+            d,
+            fill,
+            stroke,
+            escape(&poly.metadata.name)
+        );
+    }
+    out.push_str("</g>\n");
+
+    out.push_str("<g fill=\"none\" stroke-width=\"1\">\n");
+    for collection in ctx.polylines {
+        // Roughly the terminal's dash, scaled from dots to pixels.
+        let dasharray = dash_pattern(collection.dash)
+            .map(|(on, off)| format!(r#" stroke-dasharray="{} {}""#, on * 2.0, off * 2.0))
+            .unwrap_or_default();
+        for line in collection.lines {
+            let d = frame.clipped_path(line.inner.coords());
+            if !d.is_empty() {
+                let _ = writeln!(
+                    out,
+                    r#"<path d="{}" stroke="{}"{}/>"#,
+                    d,
+                    color(collection.color),
+                    dasharray
+                );
+            }
+        }
+    }
+    out.push_str("</g>\n");
+
+    let _ = writeln!(out, r#"<g fill="{}">"#, color(theme.point));
+    for pt in ctx.points {
+        if frame.contains(Coord { x: pt.x, y: pt.y }) {
+            let (x, y) = frame.px(pt.x, pt.y);
+            let _ = writeln!(out, r#"<circle cx="{:.1}" cy="{:.1}" r="2.5"/>"#, x, y);
+        }
+    }
+    out.push_str("</g>\n");
+
+    let _ = writeln!(
+        out,
+        r#"<g font-size="13" text-anchor="middle" dominant-baseline="central">"#
+    );
+    for marker in ctx.markers {
+        if frame.contains(Coord {
+            x: marker.at.x,
+            y: marker.at.y,
+        }) {
+            let (x, y) = frame.px(marker.at.x, marker.at.y);
+            let fill = if marker.selected {
+                theme.selection
+            } else {
+                theme.point
+            };
+            let _ = writeln!(
+                out,
+                r#"<text x="{:.1}" y="{:.1}" fill="{}">{}<title>{}</title></text>"#,
+                x,
+                y,
+                color(fill),
+                escape(marker.glyph),
+                escape(marker.label)
+            );
+        }
+    }
+    out.push_str("</g>\n");
+
+    // Labels go on top of every shape so fills never hide them.
+    out.push_str(&format!(
+        "<g font-size=\"11\" fill=\"{}\" text-anchor=\"middle\" stroke=\"{}\" stroke-width=\"3\" paint-order=\"stroke\">\n",
+        foreground, background
+    ));
+    for poly in ctx.regions {
+        let Some(label_at) = poly.inner.interior_point() else {
+            continue;
+        };
+        if frame.contains(label_at.0) {
+            let (x, y) = frame.px(label_at.x(), label_at.y());
+            let _ = writeln!(
+                out,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                x,
+                y,
+                escape(&poly.metadata.name)
+            );
+        }
+    }
+    out.push_str("</g>\n");
+
+    if map.show_location {
+        let (x, y) = frame.px(ctx.center.x, ctx.center.y);
+        let _ = writeln!(
+            out,
+            r#"<text x="{:.1}" y="{:.1}" font-size="16" font-weight="bold" fill="{}" text-anchor="middle" dominant-baseline="central">X</text>"#,
+            x,
+            y,
+            color(theme.marker)
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        r#"<text x="8" y="18" font-size="14" fill="{}">{}</text>"#,
+        foreground,
+        escape(ctx.title)
+    );
+
+    if let (Some(fill_info), Some(max)) = (&ctx.fill_info, max_fill_value) {
+        legend(&mut out, &frame, fill_info, max, &foreground);
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn legend(
+    out: &mut String,
+    frame: &Frame,
+    fill_info: &crate::components::map_view::FillByValue,
+    max: f64,
+    foreground: &str,
+) {
+    const W: f64 = 160.0;
+    const H: f64 = 10.0;
+    let x = 12.0;
+    let y = frame.height - 28.0;
+    out.push_str("<defs><linearGradient id=\"legend\">\n");
+    for i in 0..=10 {
+        let t = i as f64 / 10.0;
+        let _ = writeln!(
+            out,
+            r#"<stop offset="{:.1}" stop-color="{}"/>"#,
+            t,
+            color(fill_info.map.sample(t))
+        );
+    }
+    out.push_str("</linearGradient></defs>\n");
+    let _ = writeln!(
+        out,
+        r#"<g font-size="11" fill="{fg}"><text x="{x}" y="{ty}">{label}</text><rect x="{x}" y="{y}" width="{W}" height="{H}" fill="url(#legend)" stroke="{fg}" stroke-width="0.5"/><text x="{x}" y="{vy}">0</text><text x="{rx}" y="{vy}" text-anchor="end">{max}</text></g>"#,
+        fg = foreground,
+        x = x,
+        y = y,
+        ty = y - 4.0,
+        vy = y + H + 12.0,
+        rx = x + W,
+        W = W,
+        H = H,
+        label = escape(&fill_info.label),
+        max = format_value(max),
+    );
+}
+
+fn format_value(v: f64) -> String {
+    if v.abs() >= 1000.0 {
+        format!("{:.0}", v)
+    } else {
+        format!("{:.2}", v)
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    borrow::Cow,
+    io::{self, stdout, Write},
+    sync::OnceLock,
+};
+
+use crossterm::{
+    cursor::{self, MoveTo},
+    style::Print,
+    terminal::{Clear, ClearType},
+    QueueableCommand,
+};
+
+/// Characters used to draw map points, set by `ui.glyphs`. Every set is
+/// addressed by the same 2x4 sub-cell bitmask; coarser sets merge sub-cells.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphSet {
+    /// Probed from the terminal on startup.
+    #[default]
+    Auto,
+    /// Unicode 16 block octants: one block per point, at full resolution.
+    Octant,
+    /// Braille dots, same resolution as the octants.
+    Braille,
+    /// Quadrant blocks, 2x2 per cell.
+    Quadrant,
+    /// `.:*#` by how many points share a cell.
+    Ascii,
+}
+
+/// The set [`probe`] settled on, if it ran.
+static DETECTED: OnceLock<GlyphSet> = OnceLock::new();
+
+impl GlyphSet {
+    /// `Auto` replaced by what the startup probe found. Without a probe, e.g.
+    /// in headless renders, that is the octants.
+    pub fn resolve(self) -> Self {
+        match self {
+            GlyphSet::Auto => DETECTED.get().copied().unwrap_or(GlyphSet::Octant),
+            set => set,
+        }
+    }
+
+    /// The character for a cell whose lit sub-cells are `mask`, numbered as in
+    /// [`OCTANT_TABLE`].
+    pub fn glyph(self, mask: u8) -> Cow<'static, str> {
+        match self.resolve() {
+            GlyphSet::Auto | GlyphSet::Octant => Cow::Borrowed(OCTANT_TABLE[mask as usize]),
+            GlyphSet::Braille => {
+                let dots = (0..8)
+                    .filter(|bit| mask & (1 << bit) != 0)
+                    .fold(0, |acc, bit| acc | BRAILLE_DOTS[bit]);
+                let c = char::from_u32(0x2800 + dots).unwrap_or(' ');
+                Cow::Owned(c.to_string())
+            }
+            GlyphSet::Quadrant => {
+                let quadrants = QUADRANT_MASKS
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, m)| mask & **m != 0)
+                    .fold(0, |acc, (i, _)| acc | (1 << i));
+                Cow::Borrowed(QUADRANT_TABLE[quadrants])
+            }
+            GlyphSet::Ascii => Cow::Borrowed(match mask.count_ones() {
+                0 => " ",
+                1 => ".",
+                2 => ":",
+                3 | 4 => "*",
+                _ => "#",
+            }),
+        }
+    }
+
+    /// The marker for the `index`th POI category. Repeats after eight.
+    pub fn category_glyph(self, index: usize) -> &'static str {
+        let glyphs = match self.resolve() {
+            GlyphSet::Ascii => ASCII_CATEGORY_GLYPHS,
+            _ => CATEGORY_GLYPHS,
+        };
+        glyphs[index % glyphs.len()]
+    }
+
+    /// A character only this set uses, to measure whether the terminal knows it.
+    fn test_glyph(self) -> &'static str {
+        match self {
+            GlyphSet::Auto | GlyphSet::Octant => OCTANT_TABLE[4],
+            GlyphSet::Braille => "\u{28FF}",
+            GlyphSet::Quadrant => QUADRANT_TABLE[6],
+            GlyphSet::Ascii => "#",
+        }
+    }
+}
+
+/// Find the richest glyph set the terminal can draw, for `GlyphSet::Auto`.
+/// Each candidate's test glyph is printed at the top-left corner and the
+/// cursor position read back: a terminal that knows the character advances
+/// one cell, one that doesn't typically draws a two-cell placeholder or
+/// nothing. Run once in the alternate screen, before the first frame, which
+/// paints over the test glyphs. A terminal that doesn't report its cursor
+/// gets braille, which the map's lines are drawn with anyway.
+pub fn probe() {
+    let mut set = GlyphSet::Ascii;
+    for candidate in [GlyphSet::Octant, GlyphSet::Braille, GlyphSet::Quadrant] {
+        match advance(candidate.test_glyph()) {
+            Ok(1) => {
+                set = candidate;
+                break;
+            }
+            Ok(_) => continue,
+            // No cursor report; don't wait for one per candidate.
+            Err(_) => {
+                set = GlyphSet::Braille;
+                break;
+            }
+        }
+    }
+    let _ = DETECTED.set(set);
+}
+
+/// How many cells the cursor moves after printing `glyph`.
+fn advance(glyph: &str) -> io::Result<u16> {
+    let mut out = stdout();
+    out.queue(MoveTo(0, 0))?
+        .queue(Clear(ClearType::CurrentLine))?
+        .queue(Print(glyph))?;
+    out.flush()?;
+    let (col, _) = cursor::position()?;
+    out.queue(MoveTo(0, 0))?
+        .queue(Clear(ClearType::CurrentLine))?;
+    out.flush()?;
+    Ok(col)
+}
+
+/// POI markers, from the Geometric Shapes and Dingbats blocks that fonts
+/// have carried for decades.
+const CATEGORY_GLYPHS: [&str; 8] = ["●", "▲", "■", "◆", "★", "✚", "▼", "◉"];
+const ASCII_CATEGORY_GLYPHS: [&str; 8] = ["o", "^", "+", "x", "@", "%", "&", "$"];
+
+/// Braille dot bit for each octant bit. Braille numbers its dots down the
+/// left column then the right, with the bottom row added last.
+const BRAILLE_DOTS: [u32; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+
+/// Octant bits covered by each quadrant: top-left, top-right, bottom-left,
+/// bottom-right.
+const QUADRANT_MASKS: [u8; 4] = [0x05, 0x0a, 0x50, 0xa0];
+
+/// Quadrant blocks indexed by which of [`QUADRANT_MASKS`] are lit.
+#[rustfmt::skip]
+const QUADRANT_TABLE: [&str; 16] = [
+    " ", "\u{2598}", "\u{259D}", "\u{2580}", "\u{2596}", "\u{258C}", "\u{259E}", "\u{259B}",
+    "\u{2597}", "\u{259A}", "\u{2590}", "\u{259C}", "\u{2584}", "\u{2599}", "\u{259F}", "\u{2588}",
+];
+
+/// 256-entry lookup mapping an octant bitmask to its rendering character.
+/// Bit numbering is row-major over a 2x4 sub-cell grid:
+///   bit 0 (mask   1) = (col 0, row 0) top-left
+///   bit 1 (mask   2) = (col 1, row 0) top-right
+///   bit 2 (mask   4) = (col 0, row 1)
+///   bit 3 (mask   8) = (col 1, row 1)
+///   bit 4 (mask  16) = (col 0, row 2)
+///   bit 5 (mask  32) = (col 1, row 2)
+///   bit 6 (mask  64) = (col 0, row 3) bottom-left
+///   bit 7 (mask 128) = (col 1, row 3) bottom-right
+///
+/// Most entries are from the Unicode 16 "Block Octants" block (U+1CD00..U+1CDE5);
+/// remaining entries fall back to legacy quadrants (U+2596..U+259F), half/quarter
+/// blocks (U+2580..U+2590), and a few characters from the Symbols for Legacy
+/// Computing Supplement (U+1CEA0..) and Symbols for Legacy Computing (U+1FB80..).
+///
+/// **Terminal caveat**: requires Unicode 16-aware fonts (Sept 2024). Modern
+/// terminals (kitty, recent iTerm2, WezTerm, Ghostty) render the full table;
+/// older terminals will show tofu for the U+1CDxx and U+1CExx ranges, which is
+/// what the other [`GlyphSet`]s are for.
+#[rustfmt::skip]
+const OCTANT_TABLE: [&str; 256] = [
+    "\u{0020}", "\u{1CEA8}", "\u{1CEAB}", "\u{1FB82}", "\u{1CD00}", "\u{2598}", "\u{1CD01}", "\u{1CD02}",
+    "\u{1CD03}", "\u{1CD04}", "\u{259D}", "\u{1CD05}", "\u{1CD06}", "\u{1CD07}", "\u{1CD08}", "\u{2580}",
+    "\u{1CD09}", "\u{1CD0A}", "\u{1CD0B}", "\u{1CD0C}", "\u{1FBE6}", "\u{1CD0D}", "\u{1CD0E}", "\u{1CD0F}",
+    "\u{1CD10}", "\u{1CD11}", "\u{1CD12}", "\u{1CD13}", "\u{1CD14}", "\u{1CD15}", "\u{1CD16}", "\u{1CD17}",
+    "\u{1CD18}", "\u{1CD19}", "\u{1CD1A}", "\u{1CD1B}", "\u{1CD1C}", "\u{1CD1D}", "\u{1CD1E}", "\u{1CD1F}",
+    "\u{1FBE7}", "\u{1CD20}", "\u{1CD21}", "\u{1CD22}", "\u{1CD23}", "\u{1CD24}", "\u{1CD25}", "\u{1CD26}",
+    "\u{1CD27}", "\u{1CD28}", "\u{1CD29}", "\u{1CD2A}", "\u{1CD2B}", "\u{1CD2C}", "\u{1CD2D}", "\u{1CD2E}",
+    "\u{1CD2F}", "\u{1CD30}", "\u{1CD31}", "\u{1CD32}", "\u{1CD33}", "\u{1CD34}", "\u{1CD35}", "\u{1FB85}",
+    "\u{1CEA3}", "\u{1CD36}", "\u{1CD37}", "\u{1CD38}", "\u{1CD39}", "\u{1CD3A}", "\u{1CD3B}", "\u{1CD3C}",
+    "\u{1CD3D}", "\u{1CD3E}", "\u{1CD3F}", "\u{1CD40}", "\u{1CD41}", "\u{1CD42}", "\u{1CD43}", "\u{1CD44}",
+    "\u{2596}", "\u{1CD45}", "\u{1CD46}", "\u{1CD47}", "\u{1CD48}", "\u{258C}", "\u{1CD49}", "\u{1CD4A}",
+    "\u{1CD4B}", "\u{1CD4C}", "\u{259E}", "\u{1CD4D}", "\u{1CD4E}", "\u{1CD4F}", "\u{1CD50}", "\u{259B}",
+    "\u{1CD51}", "\u{1CD52}", "\u{1CD53}", "\u{1CD54}", "\u{1CD55}", "\u{1CD56}", "\u{1CD57}", "\u{1CD58}",
+    "\u{1CD59}", "\u{1CD5A}", "\u{1CD5B}", "\u{1CD5C}", "\u{1CD5D}", "\u{1CD5E}", "\u{1CD5F}", "\u{1CD60}",
+    "\u{1CD61}", "\u{1CD62}", "\u{1CD63}", "\u{1CD64}", "\u{1CD65}", "\u{1CD66}", "\u{1CD67}", "\u{1CD68}",
+    "\u{1CD69}", "\u{1CD6A}", "\u{1CD6B}", "\u{1CD6C}", "\u{1CD6D}", "\u{1CD6E}", "\u{1CD6F}", "\u{1CD70}",
+    "\u{1CEA0}", "\u{1CD71}", "\u{1CD72}", "\u{1CD73}", "\u{1CD74}", "\u{1CD75}", "\u{1CD76}", "\u{1CD77}",
+    "\u{1CD78}", "\u{1CD79}", "\u{1CD7A}", "\u{1CD7B}", "\u{1CD7C}", "\u{1CD7D}", "\u{1CD7E}", "\u{1CD7F}",
+    "\u{1CD80}", "\u{1CD81}", "\u{1CD82}", "\u{1CD83}", "\u{1CD84}", "\u{1CD85}", "\u{1CD86}", "\u{1CD87}",
+    "\u{1CD88}", "\u{1CD89}", "\u{1CD8A}", "\u{1CD8B}", "\u{1CD8C}", "\u{1CD8D}", "\u{1CD8E}", "\u{1CD8F}",
+    "\u{2597}", "\u{1CD90}", "\u{1CD91}", "\u{1CD92}", "\u{1CD93}", "\u{259A}", "\u{1CD94}", "\u{1CD95}",
+    "\u{1CD96}", "\u{1CD97}", "\u{2590}", "\u{1CD98}", "\u{1CD99}", "\u{1CD9A}", "\u{1CD9B}", "\u{259C}",
+    "\u{1CD9C}", "\u{1CD9D}", "\u{1CD9E}", "\u{1CD9F}", "\u{1CDA0}", "\u{1CDA1}", "\u{1CDA2}", "\u{1CDA3}",
+    "\u{1CDA4}", "\u{1CDA5}", "\u{1CDA6}", "\u{1CDA7}", "\u{1CDA8}", "\u{1CDA9}", "\u{1CDAA}", "\u{1CDAB}",
+    "\u{2582}", "\u{1CDAC}", "\u{1CDAD}", "\u{1CDAE}", "\u{1CDAF}", "\u{1CDB0}", "\u{1CDB1}", "\u{1CDB2}",
+    "\u{1CDB3}", "\u{1CDB4}", "\u{1CDB5}", "\u{1CDB6}", "\u{1CDB7}", "\u{1CDB8}", "\u{1CDB9}", "\u{1CDBA}",
+    "\u{1CDBB}", "\u{1CDBC}", "\u{1CDBD}", "\u{1CDBE}", "\u{1CDBF}", "\u{1CDC0}", "\u{1CDC1}", "\u{1CDC2}",
+    "\u{1CDC3}", "\u{1CDC4}", "\u{1CDC5}", "\u{1CDC6}", "\u{1CDC7}", "\u{1CDC8}", "\u{1CDC9}", "\u{1CDCA}",
+    "\u{1CDCB}", "\u{1CDCC}", "\u{1CDCD}", "\u{1CDCE}", "\u{1CDCF}", "\u{1CDD0}", "\u{1CDD1}", "\u{1CDD2}",
+    "\u{1CDD3}", "\u{1CDD4}", "\u{1CDD5}", "\u{1CDD6}", "\u{1CDD7}", "\u{1CDD8}", "\u{1CDD9}", "\u{1CDDA}",
+    "\u{2584}", "\u{1CDDB}", "\u{1CDDC}", "\u{1CDDD}", "\u{1CDDE}", "\u{2599}", "\u{1CDDF}", "\u{1CDE0}",
+    "\u{1CDE1}", "\u{1CDE2}", "\u{259F}", "\u{1CDE3}", "\u{2586}", "\u{1CDE4}", "\u{1CDE5}", "\u{2588}",
+];
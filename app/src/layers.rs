@@ -1,6 +1,7 @@
+pub mod builtin;
+
 use std::{
     collections::HashMap,
-    error::Error,
     io::{Read, Write},
     path::PathBuf,
     process::{Command, Stdio},
@@ -8,7 +9,16 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::{config::LayerConfig, update::Update};
+use crate::{
+    config::{LayerConfig, LayerKind},
+    domain::{
+        lines::{parse_line_collection, LineCollection, LineCollectionEntry},
+        location::Location,
+        poi::{parse_poi_collection, PoiCollection, PoiCollectionEntry},
+    },
+    error::LayerError,
+    update::Update,
+};
 
 const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
@@ -20,40 +30,140 @@ pub fn spawn_layer_load(
     tx: mpsc::Sender<Update>,
 ) {
     std::thread::spawn(move || {
-        let update = match run_layer_command(&config, &location_id, &region_ids, &data_root) {
-            Ok(layer_data) => Update::SetLayerData {
-                location_id,
-                layer_id: config.id,
-                layer_data,
-            },
-            Err(e) => Update::SetLayerFailed {
-                location_id,
-                layer_id: config.id,
-                err_msg: e.to_string(),
-            },
+        let loaded = match config.kind {
+            LayerKind::Values => run_layer_command(&config, &location_id, &region_ids, &data_root)
+                .map(|layer_data| Update::SetLayerData {
+                    location_id: location_id.clone(),
+                    layer_id: config.id.clone(),
+                    layer_data,
+                }),
+            LayerKind::Pois => run_poi_command(&config, &location_id, &region_ids, &data_root)
+                .map(|collections| Update::SetLayerPois {
+                    location_id: location_id.clone(),
+                    layer_id: config.id.clone(),
+                    collections,
+                }),
+            LayerKind::Lines => run_lines_command(&config, &location_id, &region_ids, &data_root)
+                .map(|collections| Update::SetLayerLines {
+                    location_id: location_id.clone(),
+                    layer_id: config.id.clone(),
+                    collections,
+                }),
         };
+        let update = loaded.unwrap_or_else(|err| Update::SetLayerFailed {
+            location_id,
+            layer_id: config.id,
+            err,
+        });
         let _ = tx.send(update);
     });
 }
 
+/// A `values` layer's values for `location`: worked out here for a built-in
+/// layer, after its source layer's from `layers`, otherwise by running its
+/// command.
+pub fn layer_values(
+    config: &LayerConfig,
+    layers: &[LayerConfig],
+    location: &Location,
+    data_root: &PathBuf,
+) -> Result<HashMap<String, f64>, LayerError> {
+    if let Some(builtin) = config.builtin {
+        let source = match &config.source {
+            Some(id) => {
+                let source = layers
+                    .iter()
+                    .find(|l| &l.id == id)
+                    .ok_or_else(|| LayerError::Source { layer: id.clone() })?;
+                Some(layer_values(source, layers, location, data_root)?)
+            }
+            None => None,
+        };
+        return Ok(builtin::compute(builtin, location, source.as_ref()));
+    }
+    let region_ids: Vec<String> = location
+        .regions
+        .iter()
+        .map(|p| p.metadata.id.clone())
+        .collect();
+    run_layer_command(config, &location.tag.id, &region_ids, data_root)
+}
+
 pub fn run_layer_command(
     config: &LayerConfig,
     location_id: &str,
     region_ids: &[String],
     data_root: &PathBuf,
-) -> Result<HashMap<String, f64>, Box<dyn Error>> {
+) -> Result<HashMap<String, f64>, LayerError> {
+    let stdout = run_command(config, location_id, region_ids, data_root)?;
+    serde_json::from_slice(&stdout).map_err(|source| LayerError::Parse {
+        command: config.command.clone(),
+        source,
+    })
+}
+
+/// Run a `pois` layer, which prints collections in the same shape as a
+/// polygon file's `pois` list.
+pub fn run_poi_command(
+    config: &LayerConfig,
+    location_id: &str,
+    region_ids: &[String],
+    data_root: &PathBuf,
+) -> Result<Vec<PoiCollection>, LayerError> {
+    let stdout = run_command(config, location_id, region_ids, data_root)?;
+    let entries: Vec<PoiCollectionEntry> =
+        serde_json::from_slice(&stdout).map_err(|source| LayerError::ParsePois {
+            command: config.command.clone(),
+            source,
+        })?;
+    Ok(entries.into_iter().map(parse_poi_collection).collect())
+}
+
+/// Run a `lines` layer, which prints collections in the same shape as a
+/// polygon file's `lines` list.
+pub fn run_lines_command(
+    config: &LayerConfig,
+    location_id: &str,
+    region_ids: &[String],
+    data_root: &PathBuf,
+) -> Result<Vec<LineCollection>, LayerError> {
+    let stdout = run_command(config, location_id, region_ids, data_root)?;
+    let entries: Vec<LineCollectionEntry> =
+        serde_json::from_slice(&stdout).map_err(|source| LayerError::ParseLines {
+            command: config.command.clone(),
+            source,
+        })?;
+    Ok(entries.into_iter().map(parse_line_collection).collect())
+}
+
+/// Run a layer's command with the region ids on stdin and return what it
+/// printed.
+fn run_command(
+    config: &LayerConfig,
+    location_id: &str,
+    region_ids: &[String],
+    data_root: &PathBuf,
+) -> Result<Vec<u8>, LayerError> {
+    let io_err = |source| LayerError::Io {
+        command: config.command.clone(),
+        source,
+    };
     let mut child = Command::new(&config.command)
         .env("LOCTUI_LOCATION_ID", location_id)
         .env("LOCTUI_DATA_ROOT", data_root)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .spawn()?;
+        .spawn()
+        .map_err(|source| LayerError::Spawn {
+            command: config.command.clone(),
+            source,
+        })?;
 
-    {
-        let stdin = child.stdin.as_mut().ok_or("Could not open stdin")?;
-        let payload = serde_json::to_string(region_ids)?;
-        stdin.write_all(payload.as_bytes())?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        // A Vec<String> always serializes.
+        let payload = serde_json::to_string(region_ids).unwrap_or_default();
+        stdin.write_all(payload.as_bytes()).map_err(io_err)?;
     }
     // Close stdin so the child sees EOF and can complete.
     drop(child.stdin.take());
@@ -65,17 +175,16 @@ pub fn run_layer_command(
     let timeout = Duration::from_secs(config.timeout_secs);
     let start = Instant::now();
     let status = loop {
-        match child.try_wait()? {
+        match child.try_wait().map_err(io_err)? {
             Some(s) => break s,
             None => {
                 if start.elapsed() >= timeout {
                     let _ = child.kill();
                     let _ = child.wait();
-                    return Err(format!(
-                        "Layer command timed out after {}s",
-                        timeout.as_secs()
-                    )
-                    .into());
+                    return Err(LayerError::Timeout {
+                        command: config.command.clone(),
+                        secs: timeout.as_secs(),
+                    });
                 }
                 std::thread::sleep(POLL_INTERVAL);
             }
@@ -84,18 +193,20 @@ pub fn run_layer_command(
 
     let mut stdout = Vec::new();
     if let Some(mut s) = child.stdout.take() {
-        s.read_to_end(&mut stdout)?;
+        s.read_to_end(&mut stdout).map_err(io_err)?;
     }
     let mut stderr = Vec::new();
     if let Some(mut s) = child.stderr.take() {
-        s.read_to_end(&mut stderr)?;
+        s.read_to_end(&mut stderr).map_err(io_err)?;
     }
 
     if !status.success() {
-        let stderr = String::from_utf8_lossy(&stderr);
-        return Err(format!("Layer command failed {}", stderr.trim()).into());
+        return Err(LayerError::Failed {
+            command: config.command.clone(),
+            status,
+            stderr: String::from_utf8_lossy(&stderr).trim().to_string(),
+        });
     }
 
-    let layer_data: HashMap<String, f64> = serde_json::from_slice(&stdout)?;
-    Ok(layer_data)
+    Ok(stdout)
 }
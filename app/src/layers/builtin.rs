@@ -0,0 +1,70 @@
+use std::{collections::HashMap, f64::consts::PI};
+
+use geo::{Area, Centroid, EuclideanLength};
+
+use crate::{
+    config::BuiltinLayer,
+    domain::{geometry::Local, geometry::Polygon, location::Location},
+};
+
+/// A value per region, worked out from the region polygons and, for
+/// `neighbour_mean`, the values of its source layer. Regions the value makes
+/// no sense for (e.g. an empty ring, or no neighbour with a value) are left
+/// out.
+pub fn compute(
+    builtin: BuiltinLayer,
+    location: &Location,
+    source: Option<&HashMap<String, f64>>,
+) -> HashMap<String, f64> {
+    let regions = &location.regions;
+    let values: Vec<Option<f64>> = match builtin {
+        BuiltinLayer::Area => regions.iter().map(|r| Some(area_m2(r) / 1e6)).collect(),
+        BuiltinLayer::Perimeter => regions.iter().map(|r| Some(perimeter_m(r) / 1e3)).collect(),
+        // Polsby-Popper: 1 for a circle, towards 0 for long or ragged shapes.
+        BuiltinLayer::Compactness => regions
+            .iter()
+            .map(|r| {
+                let perimeter = perimeter_m(r);
+                (perimeter > 0.0).then(|| 4.0 * PI * area_m2(r) / perimeter.powi(2))
+            })
+            .collect(),
+        // The Local frame's origin is the location's centre.
+        BuiltinLayer::CentreDistance => regions
+            .iter()
+            .map(|r| r.inner.centroid().map(|c| c.x().hypot(c.y()) / 1e3))
+            .collect(),
+        BuiltinLayer::Neighbours => (0..regions.len())
+            .map(|i| Some(location.adjacency.of(i).len() as f64))
+            .collect(),
+        BuiltinLayer::NeighbourMean => {
+            let source: Vec<Option<f64>> = regions
+                .iter()
+                .map(|r| source?.get(&r.metadata.id).copied())
+                .collect();
+            (0..regions.len())
+                .map(|i| location.adjacency.neighbour_mean(i, &source))
+                .collect()
+        }
+    };
+    regions
+        .iter()
+        .zip(values)
+        .filter_map(|(r, v)| Some((r.metadata.id.clone(), v?)))
+        .collect()
+}
+
+/// Holes included.
+fn area_m2(region: &Polygon<Local>) -> f64 {
+    region.inner.unsigned_area()
+}
+
+/// Around the outside and every hole.
+fn perimeter_m(region: &Polygon<Local>) -> f64 {
+    region.inner.exterior().euclidean_length()
+        + region
+            .inner
+            .interiors()
+            .iter()
+            .map(|ring| ring.euclidean_length())
+            .sum::<f64>()
+}
@@ -1,52 +1,128 @@
 use std::{path::Path, sync::mpsc};
 
+use ratatui::DefaultTerminal;
+
 use crate::{
-    app::App, config::Config, db::file_db::FileDB, event::poll_and_handle_event,
-    model::ApplicationStatus, update::Update,
+    app::App, cli::Cli, config::Config, db::file_db::FileDB, error::ErrorReport,
+    event::poll_and_handle_event,
+    glyphs::GlyphSet,
+    message::Message, model::ApplicationStatus, state::SavedState, update::Update,
+    watch::FileWatcher,
 };
 
 mod app;
+mod cli;
+mod clipboard;
 mod coastlines;
+mod color;
+mod commands;
 mod component;
 mod components;
 mod config;
 mod db;
 mod domain;
+mod error;
 mod event;
+mod export;
+mod glyphs;
 mod layers;
 mod message;
 mod model;
 mod screens;
+mod snapshot;
+mod state;
+mod theme;
 mod tui;
 mod update;
 mod view;
+mod watch;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {}", ErrorReport::new(e.as_ref()));
+        std::process::exit(1);
+    }
+}
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let config: Config = match std::env::var("LOCTUI_CONFIG").ok() {
-        Some(config_path) => {
-            let raw = std::fs::read_to_string(config_path)?;
-            toml::from_str(&raw)?
+/// Start watching the open dataset and config for hot reload. Failing to
+/// watch isn't fatal: it is reported in a toast and the app runs without it.
+fn watch_files(app: &mut App, tx: &mpsc::Sender<Message>) -> Option<FileWatcher> {
+    let config = &app.model.config;
+    match FileWatcher::spawn(&config.data.root_dir, &config.source.files, tx.clone()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            app.model.apply(Update::ToastError(format!(
+                "Not watching for changes: {}",
+                e
+            )));
+            None
         }
-        None => Config::default(),
-    };
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse_checked();
+    let config = Config::load(&cli.config)?;
+    if let Some(command) = cli.command {
+        return commands::run(command, config);
+    }
+    // Open the dataset before taking over the terminal, so a bad root_dir is
+    // reported on the normal screen.
+    let db = FileDB::new(Path::new(&config.data.root_dir.join("locations.json")))?;
     tui::install_panic_hook();
     let mut terminal = tui::init_terminal()?;
+    if config.ui.glyphs == GlyphSet::Auto {
+        glyphs::probe();
+    }
 
-    let db = FileDB::new(Path::new(&config.data.root_dir.join("locations.json")))?;
     let (async_tx, async_rx) = mpsc::channel::<Update>();
-    let mut app = App::new(db, config, async_tx);
+    let (reload_tx, reload_rx) = mpsc::channel::<Message>();
+    let mut app = App::new(db, config, SavedState::load(), async_tx);
+    if let Some(session) = cli.session.to_session() {
+        app.model.restorable_session = Some(session);
+        app.handle(Message::RestoreSession);
+    }
+    let result = run_app(
+        &mut terminal,
+        &mut app,
+        &async_rx,
+        (&reload_tx, &reload_rx),
+    );
+
+    let saved = app.save_session();
+    tui::restore_terminal()?;
+    if let Err(e) = saved {
+        eprintln!("warning: {}", ErrorReport::new(&e));
+    }
+    result
+}
+
+/// The event loop. Errors end it but are returned to `run`, which restores the
+/// terminal before they are printed.
+fn run_app(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    async_rx: &mpsc::Receiver<Update>,
+    (reload_tx, reload_rx): (&mpsc::Sender<Message>, &mpsc::Receiver<Message>),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut watcher = watch_files(app, reload_tx);
     while app.model.application_status == ApplicationStatus::Running {
         terminal.draw(|frame| app.render(frame))?;
 
         while let Ok(u) = async_rx.try_recv() {
             app.model.apply(u);
         }
+        if let Ok(msg) = reload_rx.try_recv() {
+            app.handle(msg);
+            // The reloaded config may point at a different dataset or files.
+            if !watcher.as_ref().is_some_and(|w| w.watches(&app.model.config)) {
+                watcher = watch_files(app, reload_tx);
+            }
+        }
 
         if let Some(msg) = poll_and_handle_event()? {
             app.handle(msg);
         }
     }
-
-    tui::restore_terminal()?;
     Ok(())
 }
@@ -1,3 +1,7 @@
+use std::path::PathBuf;
+
+use crate::export::ExportJob;
+
 pub enum Message {
     // primitive key events
     Char(char),
@@ -9,6 +13,12 @@ pub enum Message {
     Enter,
     Esc,
     Tab,
+    /// The mouse moved over, or was clicked on, this terminal cell.
+    Mouse {
+        column: u16,
+        row: u16,
+        click: bool,
+    },
 
     // top-level
     Quit,
@@ -16,4 +26,12 @@ pub enum Message {
     // synthetic — dispatched to a screen when it becomes active
     Activated,
     LoadLayers,
+    RestoreSession,
+    /// Write an export on a background thread; handled by `App`.
+    Export {
+        path: PathBuf,
+        job: ExportJob,
+    },
+    /// The dataset or config changed on disk.
+    Reload,
 }
@@ -1,6 +1,16 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-use crate::{config::Config, domain::location::Location, update::Update};
+use crate::{
+    config::Config,
+    domain::{lines::LineCollection, location::Location, poi::PoiCollection},
+    error::{ErrorReport, LayerError},
+    layers::builtin,
+    state::{SavedState, Session},
+    update::Update,
+};
 
 pub struct InspectingState {
     pub location: Location,
@@ -27,23 +37,49 @@ pub enum ApplicationStatus {
 pub enum LayerState {
     Loading,
     Loaded(HashMap<String, f64>),
-    Failed(String),
+    /// A `pois` layer's collections.
+    LoadedPois(Vec<PoiCollection>),
+    /// A `lines` layer's collections.
+    LoadedLines(Vec<LineCollection>),
+    Failed(LayerError),
+}
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+pub struct Toast {
+    pub message: String,
+    pub error: bool,
+    pub until: Instant,
+}
+
+impl Toast {
+    pub fn visible(&self) -> bool {
+        Instant::now() < self.until
+    }
 }
 
 pub struct Model {
     pub application_status: ApplicationStatus,
     pub interaction_mode: InteractionMode,
-    pub err: Option<String>,
+    pub err: Option<ErrorReport>,
+    pub toast: Option<Toast>,
     pub config: Config,
+    pub saved_state: SavedState,
+    /// Session offered for restore on the location list, consumed by
+    /// `Message::RestoreSession`.
+    pub restorable_session: Option<Session>,
 }
 
 impl Model {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, saved_state: SavedState) -> Self {
         Self {
             application_status: ApplicationStatus::Running,
             interaction_mode: InteractionMode::BrowsingLocation,
             err: None,
+            toast: None,
             config,
+            restorable_session: saved_state.session.clone(),
+            saved_state,
         }
     }
 
@@ -51,8 +87,24 @@ impl Model {
         match update {
             Update::Quit => self.application_status = ApplicationStatus::Done,
             Update::SetError(err) => self.err = Some(err),
+            Update::Toast(message) => {
+                self.toast = Some(Toast {
+                    message,
+                    error: false,
+                    until: Instant::now() + TOAST_DURATION,
+                })
+            }
+            Update::ToastError(message) => {
+                self.toast = Some(Toast {
+                    message,
+                    error: true,
+                    until: Instant::now() + TOAST_DURATION,
+                })
+            }
             Update::ClearLocation => self.interaction_mode = InteractionMode::BrowsingLocation,
             Update::SetLocation(location) => {
+                self.saved_state.push_recent(&location.tag.id);
+                self.restorable_session = None;
                 self.interaction_mode = InteractionMode::InspectingLocation {
                     state: InspectingState {
                         location,
@@ -62,6 +114,20 @@ impl Model {
                     },
                 }
             }
+            Update::ReloadLocation(location) => {
+                if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
+                    let layer_exists = self
+                        .config
+                        .layers
+                        .iter()
+                        .any(|l| l.id == state.active_layer);
+                    if !layer_exists {
+                        state.active_layer = "boundaries".to_string();
+                    }
+                    state.location = location;
+                    state.layers.clear();
+                }
+            }
             Update::SetInspectingLocationView(new_view) => {
                 if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
                     state.view = new_view;
@@ -82,30 +148,96 @@ impl Model {
                 layer_id,
                 layer_data,
             } => {
+                let mut averages = vec![];
                 if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
                     if state.location.tag.id == location_id {
+                        averages = self
+                            .config
+                            .layers
+                            .iter()
+                            .filter(|l| l.source.as_ref() == Some(&layer_id))
+                            .filter_map(|l| {
+                                Some(Update::SetLayerData {
+                                    location_id: location_id.clone(),
+                                    layer_id: l.id.clone(),
+                                    layer_data: builtin::compute(
+                                        l.builtin?,
+                                        &state.location,
+                                        Some(&layer_data),
+                                    ),
+                                })
+                            })
+                            .collect();
                         state
                             .layers
                             .insert(layer_id, LayerState::Loaded(layer_data));
                     }
                 }
+                // Layers averaging this one can be worked out now.
+                for update in averages {
+                    self.apply(update);
+                }
+            }
+            Update::SetLayerPois {
+                location_id,
+                layer_id,
+                collections,
+            } => {
+                if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
+                    if state.location.tag.id == location_id {
+                        state
+                            .layers
+                            .insert(layer_id, LayerState::LoadedPois(collections));
+                    }
+                }
+            }
+            Update::SetLayerLines {
+                location_id,
+                layer_id,
+                collections,
+            } => {
+                if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
+                    if state.location.tag.id == location_id {
+                        state
+                            .layers
+                            .insert(layer_id, LayerState::LoadedLines(collections));
+                    }
+                }
             }
             Update::SetLayerFailed {
                 location_id,
                 layer_id,
-                err_msg,
+                err,
             } => {
+                let mut averages = vec![];
                 if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
                     if state.location.tag.id == location_id {
-                        state.layers.insert(layer_id, LayerState::Failed(err_msg));
+                        averages = self
+                            .config
+                            .layers
+                            .iter()
+                            .filter(|l| l.source.as_ref() == Some(&layer_id))
+                            .map(|l| Update::SetLayerFailed {
+                                location_id: location_id.clone(),
+                                layer_id: l.id.clone(),
+                                err: LayerError::Source {
+                                    layer: layer_id.clone(),
+                                },
+                            })
+                            .collect();
+                        state.layers.insert(layer_id, LayerState::Failed(err));
                     }
                 }
+                for update in averages {
+                    self.apply(update);
+                }
             }
             Update::SetActiveLayer { layer_id } => {
                 if let InteractionMode::InspectingLocation { state } = &mut self.interaction_mode {
                     state.active_layer = layer_id;
                 }
             }
+            Update::ToggleStar { location_id } => self.saved_state.toggle_star(&location_id),
         }
     }
 }
@@ -1,23 +1,46 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    iter::once,
+    path::PathBuf,
+};
 
+use itertools::Itertools;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, List, ListState},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
+use wkt::ToWkt;
+
 use crate::{
+    clipboard::ClipboardMethod,
     component::Component,
-    components::map_view::{ColorMap, FillByValue, MapView, MapViewCtx},
-    config::LayerConfig,
+    components::{
+        copy_flash::CopyFlash,
+        map_view::{ColorMap, FillByValue, MapLines, MapMarker, MapView, MapViewCtx},
+        path_prompt::{PathPrompt, PromptOutcome},
+    },
+    config::{LayerConfig, LayerKind},
     db::file_db::FileDB,
     domain::{
         geometry::{Local, Point},
+        lines::LineCollection,
         location::Location,
+        poi::{Poi, PoiCollection},
+    },
+    error::ErrorReport,
+    export::{
+        self,
+        regions::{region_feature, regions_tsv, write_regions, LayerColumn, RegionFormat},
+        MapFormat,
     },
     message::Message,
     model::{InspectingLocationView, LayerState},
+    theme::Theme,
     update::Update,
 };
 
@@ -29,20 +52,396 @@ pub struct InspectScreenCtx<'a> {
     pub layers: &'a HashMap<String, LayerState>,
     pub active_layer: &'a String,
     pub configured_layers: &'a [LayerConfig],
-    pub err: &'a Option<String>,
+    pub starred: bool,
+    pub clipboard: ClipboardMethod,
+    pub theme: Theme,
 }
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum CopiedField {
+    RegionId,
+    RegionName,
+    RegionValue,
+    Table,
+    GeoJson,
+    Wkt,
+    Measurement,
+}
+
+/// What the open export prompt will write.
+#[derive(Clone, Copy)]
+enum ExportTarget {
+    Map(MapFormat),
+    Regions,
+}
+
+/// The list Up and Down move through.
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum ListFocus {
+    Regions,
+    Pois,
+}
+
+/// One row of the POI list: a collection, or one of its POIs beneath it.
+#[derive(Clone, Copy)]
+enum PoiRow<'a> {
+    Collection(&'a PoiCollection),
+    Poi(&'a PoiCollection, &'a Poi),
+}
+
 pub struct InspectScreen {
     pub map: MapView<Local>,
     selected_region: Option<usize>,
+    /// Output file being chosen for an export, while the prompt is open.
+    export_prompt: Option<(PathPrompt, ExportTarget)>,
+    flash: CopyFlash<CopiedField>,
+    focus: ListFocus,
+    /// Index into [`poi_rows`].
+    selected_poi: Option<usize>,
+    /// Ids of the POI collections toggled off the map.
+    hidden_pois: HashSet<String>,
+    /// Ids of the line collections toggled off the map.
+    hidden_lines: HashSet<String>,
 }
 
 impl InspectScreen {
     pub fn new() -> Self {
         Self {
-            map: MapView::new(&[], Some(0.1), false, true),
+            map: MapView::new(None, Some(0.1), false, true),
             selected_region: None,
+            export_prompt: None,
+            flash: CopyFlash::new(),
+            focus: ListFocus::Regions,
+            selected_poi: None,
+            hidden_pois: HashSet::new(),
+            hidden_lines: HashSet::new(),
         }
     }
+
+    /// Move the POI selection one row, passing through "nothing selected"
+    /// at either end like the Regions list.
+    fn step_poi(&mut self, down: bool, rows: usize) {
+        self.selected_poi = match (self.selected_poi, down) {
+            _ if rows == 0 => None,
+            (None, true) => Some(0),
+            (None, false) => Some(rows - 1),
+            (Some(i), true) if i + 1 < rows => Some(i + 1),
+            (Some(i), false) if i > 0 && i < rows => Some(i - 1),
+            _ => None,
+        };
+    }
+
+    fn selected_poi_row<'a>(&self, rows: &[PoiRow<'a>]) -> Option<PoiRow<'a>> {
+        self.selected_poi.and_then(|i| rows.get(i)).copied()
+    }
+
+    fn render_poi_list(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        collections: &[&PoiCollection],
+        rows: &[PoiRow],
+        pending: Vec<String>,
+        theme: &Theme,
+    ) {
+        let mut block = Block::bordered().title("POIs ('o', Space to hide)");
+        if self.focus == ListFocus::Pois {
+            block = block.border_style(Style::new().fg(theme.accent));
+        }
+        if !pending.is_empty() {
+            block = block.title_bottom(pending.join(", "));
+        }
+        let items = once(ListItem::new("<None>")).chain(rows.iter().map(|row| {
+            let (collection, label) = match row {
+                PoiRow::Collection(c) => {
+                    let glyph = theme
+                        .glyphs
+                        .category_glyph(category_index(collections, &c.category));
+                    let shown = if self.hidden_pois.contains(&c.id) {
+                        ' '
+                    } else {
+                        'x'
+                    };
+                    let label = format!("[{}] {} {} ({})", shown, glyph, c.name, c.pois.len());
+                    (c, label)
+                }
+                PoiRow::Poi(c, poi) => (c, format!("    {}", poi.name)),
+            };
+            // Hidden collections stay listed, dimmed, so they can be shown again.
+            let style = if self.hidden_pois.contains(&collection.id) {
+                Style::new().add_modifier(Modifier::DIM)
+            } else {
+                Style::new()
+            };
+            ListItem::new(label).style(style)
+        }));
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        let mut list_state = ListState::default();
+        list_state.select(Some(self.selected_poi.map_or(0, |i| 1 + i)));
+        frame.render_widget(&block, area);
+        frame.render_stateful_widget(list, block.inner(area), &mut list_state);
+    }
+
+    /// Markers for every POI in a visible collection, one glyph per category.
+    fn poi_markers<'a>(
+        &self,
+        collections: &[&'a PoiCollection],
+        theme: &Theme,
+    ) -> Vec<MapMarker<'a, Local>> {
+        let selected = match self.selected_poi_row(&poi_rows(collections)) {
+            Some(PoiRow::Poi(_, poi)) => Some(poi),
+            _ => None,
+        };
+        collections
+            .iter()
+            .filter(|c| !self.hidden_pois.contains(&c.id))
+            .flat_map(|c| {
+                let glyph = theme
+                    .glyphs
+                    .category_glyph(category_index(collections, &c.category));
+                c.pois.iter().map(move |poi| MapMarker {
+                    at: poi.point,
+                    glyph,
+                    label: &poi.name,
+                    selected: selected.is_some_and(|s| std::ptr::eq(s, poi)),
+                })
+            })
+            .collect()
+    }
+
+    /// Every visible line collection, in its own colour or the theme's.
+    fn map_lines<'a>(
+        &self,
+        collections: &[&'a LineCollection],
+        theme: &Theme,
+    ) -> Vec<MapLines<'a, Local>> {
+        collections
+            .iter()
+            .filter(|c| !self.hidden_lines.contains(&c.id))
+            .map(|c| MapLines {
+                lines: &c.lines,
+                color: c.style.color.map_or(theme.polyline, |c| c.0),
+                dash: c.style.dash,
+            })
+            .collect()
+    }
+
+    /// Copy something about the selected region, the whole table or the
+    /// map's measurement. Region copies do nothing while no region is
+    /// selected.
+    fn copy(&mut self, field: CopiedField, ctx: &InspectScreenCtx) {
+        let region = self
+            .selected_region
+            .and_then(|i| ctx.location.regions.get(i));
+        let value = match (field, region) {
+            (CopiedField::Table, _) => regions_tsv(&ctx.location.regions, &loaded_layers(ctx)),
+            (CopiedField::Measurement, _) => match self.map.measurement() {
+                Some(m) => m.to_string(),
+                None => return,
+            },
+            (_, None) => return,
+            (CopiedField::RegionId, Some(r)) => r.metadata.id.clone(),
+            (CopiedField::RegionName, Some(r)) => r.metadata.name.clone(),
+            (CopiedField::RegionValue, Some(r)) => match ctx.layers.get(ctx.active_layer) {
+                Some(LayerState::Loaded(values)) => match values.get(&r.metadata.id) {
+                    Some(v) => v.to_string(),
+                    None => return,
+                },
+                _ => return,
+            },
+            (CopiedField::GeoJson, Some(r)) => {
+                format!(
+                    "{:#}",
+                    region_feature(r, &ctx.location.latlng, &loaded_layers(ctx))
+                )
+            }
+            (CopiedField::Wkt, Some(r)) => r.inner.wkt_string(),
+        };
+        self.flash.copy(field, &value, ctx.clipboard);
+    }
+
+    fn selected_neighbours<'a>(&self, location: &'a Location) -> &'a [usize] {
+        self.selected_region
+            .map_or(&[], |i| location.adjacency.of(i))
+    }
+
+    pub fn selected_region_id(&self, location: &Location) -> Option<String> {
+        self.selected_region
+            .and_then(|i| location.regions.get(i))
+            .map(|p| p.metadata.id.clone())
+    }
+
+    pub fn select_region_by_id(&mut self, location: &Location, region_id: &str) {
+        self.selected_region = location
+            .regions
+            .iter()
+            .position(|p| p.metadata.id == region_id);
+    }
+
+    /// The map as last drawn, with the active layer's fills and legend.
+    pub fn map_svg(&self, ctx: &InspectScreenCtx) -> Result<String, Box<dyn Error>> {
+        let title = map_title(ctx);
+        let markers = self.poi_markers(&poi_collections(ctx), &ctx.theme);
+        let lines = self.map_lines(&line_collections(ctx), &ctx.theme);
+        let map_ctx = MapViewCtx {
+            center: &ORIGIN,
+            boundaries: &ctx.location.boundaries,
+            regions: &ctx.location.regions,
+            region_index: Some(&ctx.location.index),
+            levels: Some(&ctx.location.levels),
+            generation: ctx.location.generation,
+            polylines: &lines,
+            points: &[],
+            markers: &markers,
+            title: &title,
+            selected_region: &self.selected_region,
+            neighbours: self.selected_neighbours(ctx.location),
+            fill_info: layer_fills(ctx),
+            origin: Some(&ctx.location.latlng),
+            theme: ctx.theme,
+        };
+        export::map_svg(&self.map, &map_ctx)
+    }
+}
+
+/// Write every region with all loaded layers, in the format the path's
+/// extension names.
+fn export_regions(path: PathBuf, ctx: &InspectScreenCtx) -> Update {
+    let Some(format) = RegionFormat::from_path(&path) else {
+        return export::report(
+            path,
+            Err("use a .csv, .geojson or .parquet file name".into()),
+        );
+    };
+    let result = write_regions(&path, ctx.location, &loaded_layers(ctx), format);
+    export::report(path, result)
+}
+
+/// Every layer that has finished loading, in config order.
+fn loaded_layers<'a>(ctx: &InspectScreenCtx<'a>) -> Vec<LayerColumn<'a>> {
+    ctx.configured_layers
+        .iter()
+        .filter_map(|l| match ctx.layers.get(&l.id) {
+            Some(LayerState::Loaded(values)) => Some(LayerColumn { id: &l.id, values }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Layers that fill regions, in config order. `pois` and `lines` layers are
+/// listed with what they draw instead.
+fn value_layers<'a>(ctx: &InspectScreenCtx<'a>) -> impl Iterator<Item = &'a LayerConfig> {
+    ctx.configured_layers
+        .iter()
+        .filter(|l| l.kind == LayerKind::Values)
+}
+
+/// The location's own POI collections, then those of every loaded `pois`
+/// layer in config order.
+fn poi_collections<'a>(ctx: &InspectScreenCtx<'a>) -> Vec<&'a PoiCollection> {
+    let from_layers = ctx
+        .configured_layers
+        .iter()
+        .filter_map(|l| match ctx.layers.get(&l.id) {
+            Some(LayerState::LoadedPois(collections)) => Some(collections),
+            _ => None,
+        })
+        .flatten();
+    ctx.location.pois.iter().chain(from_layers).collect()
+}
+
+/// The location's own line collections, then those of every loaded `lines`
+/// layer in config order.
+fn line_collections<'a>(ctx: &InspectScreenCtx<'a>) -> Vec<&'a LineCollection> {
+    let from_layers = ctx
+        .configured_layers
+        .iter()
+        .filter_map(|l| match ctx.layers.get(&l.id) {
+            Some(LayerState::LoadedLines(collections)) => Some(collections),
+            _ => None,
+        })
+        .flatten();
+    ctx.location.lines.iter().chain(from_layers).collect()
+}
+
+fn poi_rows<'a>(collections: &[&'a PoiCollection]) -> Vec<PoiRow<'a>> {
+    collections
+        .iter()
+        .flat_map(|c| {
+            once(PoiRow::Collection(c)).chain(c.pois.iter().map(move |p| PoiRow::Poi(c, p)))
+        })
+        .collect()
+}
+
+/// Categories are numbered by first appearance, hidden collections
+/// included, so a category keeps its glyph while others are toggled.
+fn category_index(collections: &[&PoiCollection], category: &str) -> usize {
+    collections
+        .iter()
+        .map(|c| c.category.as_str())
+        .unique()
+        .position(|c| c == category)
+        .unwrap_or_default()
+}
+
+/// Layers of `kind` still loading or failed, for the lists that show what
+/// they give.
+fn pending_layers(ctx: &InspectScreenCtx, kind: LayerKind) -> Vec<String> {
+    ctx.configured_layers
+        .iter()
+        .filter(|l| l.kind == kind)
+        .filter_map(|l| match ctx.layers.get(&l.id) {
+            Some(LayerState::Loading) => Some(format!("{} - Loading", l.name)),
+            Some(LayerState::Failed(err)) => Some(format!(
+                "{} - Failed - {}",
+                l.name,
+                ErrorReport::new(err).one_line()
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn map_title(ctx: &InspectScreenCtx) -> String {
+    if ctx.starred {
+        format!("★ {}", ctx.location.tag.name)
+    } else {
+        ctx.location.tag.name.clone()
+    }
+}
+
+/// The active layer's value for each region, in region order, once it has
+/// loaded.
+fn region_values(ctx: &InspectScreenCtx) -> Option<Vec<Option<f64>>> {
+    let Some(LayerState::Loaded(values)) = ctx.layers.get(ctx.active_layer) else {
+        return None;
+    };
+    Some(
+        ctx.location
+            .regions
+            .iter()
+            .map(|r| values.get(&r.metadata.id).copied())
+            .collect(),
+    )
+}
+
+fn layer_fills(ctx: &InspectScreenCtx) -> Option<FillByValue> {
+    let Some(LayerState::Loaded(values)) = ctx.layers.get(ctx.active_layer) else {
+        return None;
+    };
+    let label = ctx
+        .configured_layers
+        .iter()
+        .find(|l| &l.id == ctx.active_layer)
+        .map(|l| l.name.clone())
+        .unwrap_or_else(|| ctx.active_layer.clone());
+    Some(FillByValue {
+        label,
+        map: ColorMap::magma(),
+        values: values.clone(),
+    })
 }
 
 impl Component for InspectScreen {
@@ -53,7 +452,30 @@ impl Component for InspectScreen {
         ctx: InspectScreenCtx,
         db: &FileDB,
     ) -> (Vec<Update>, Vec<Message>) {
+        if let Some((prompt, target)) = &mut self.export_prompt {
+            let target = *target;
+            return match prompt.update(msg) {
+                PromptOutcome::Editing => (vec![], vec![]),
+                PromptOutcome::Cancelled => {
+                    self.export_prompt = None;
+                    (vec![], vec![])
+                }
+                PromptOutcome::Submitted(path) => {
+                    self.export_prompt = None;
+                    match target {
+                        ExportTarget::Map(format) => {
+                            export::export_map(path, self.map_svg(&ctx), format)
+                        }
+                        ExportTarget::Regions => (vec![export_regions(path, &ctx)], vec![]),
+                    }
+                }
+            };
+        }
         match msg {
+            Message::Esc if self.map.cursor().is_some() || self.map.measurement().is_some() => {
+                self.map.hide_cursor();
+                return (vec![], vec![]);
+            }
             Message::Esc => return (vec![Update::ClearLocation], vec![]),
             Message::Tab => {
                 return (
@@ -63,6 +485,100 @@ impl Component for InspectScreen {
                     vec![Message::Activated],
                 )
             }
+            Message::Char('*') => {
+                return (
+                    vec![Update::ToggleStar {
+                        location_id: ctx.location.tag.id.clone(),
+                    }],
+                    vec![],
+                )
+            }
+            Message::Char('S') | Message::Char('P') => {
+                let format = match msg {
+                    Message::Char('S') => MapFormat::Svg,
+                    _ => MapFormat::Png,
+                };
+                let path = format!(
+                    "{}-{}.{}",
+                    ctx.location.tag.id,
+                    ctx.active_layer,
+                    format.extension()
+                );
+                let title = format!("Export map as {}", format.extension().to_uppercase());
+                self.export_prompt =
+                    Some((PathPrompt::new(title, path), ExportTarget::Map(format)));
+                return (vec![], vec![]);
+            }
+            Message::Char(c @ ('i' | 'n' | 'v' | 'T' | 'G' | 'W' | 'y')) => {
+                let field = match c {
+                    'i' => CopiedField::RegionId,
+                    'n' => CopiedField::RegionName,
+                    'v' => CopiedField::RegionValue,
+                    'T' => CopiedField::Table,
+                    'G' => CopiedField::GeoJson,
+                    'W' => CopiedField::Wkt,
+                    _ => CopiedField::Measurement,
+                };
+                self.copy(field, &ctx);
+                return (vec![], vec![]);
+            }
+            Message::Char('E') => {
+                let path = format!("{}.{}", ctx.location.tag.id, RegionFormat::Csv.extension());
+                let prompt = PathPrompt::new("Export regions to (.csv, .geojson, .parquet)", path);
+                self.export_prompt = Some((prompt, ExportTarget::Regions));
+                return (vec![], vec![]);
+            }
+            Message::Char('o') => {
+                if self.focus == ListFocus::Pois || !poi_collections(&ctx).is_empty() {
+                    self.focus = match self.focus {
+                        ListFocus::Regions => ListFocus::Pois,
+                        ListFocus::Pois => ListFocus::Regions,
+                    };
+                }
+                return (vec![], vec![]);
+            }
+            Message::Char(' ') if self.focus == ListFocus::Pois => {
+                let collections = poi_collections(&ctx);
+                if let Some(PoiRow::Collection(c) | PoiRow::Poi(c, _)) =
+                    self.selected_poi_row(&poi_rows(&collections))
+                {
+                    if !self.hidden_pois.remove(&c.id) {
+                        self.hidden_pois.insert(c.id.clone());
+                    }
+                }
+                return (vec![], vec![]);
+            }
+            Message::Char(c @ '1'..='9') => {
+                let index = *c as usize - '1' as usize;
+                if let Some(collection) = line_collections(&ctx).get(index) {
+                    if !self.hidden_lines.remove(&collection.id) {
+                        self.hidden_lines.insert(collection.id.clone());
+                    }
+                }
+                return (vec![], vec![]);
+            }
+            // Walk to the neighbour lying most nearly that way.
+            Message::Char(c @ ('H' | 'J' | 'K' | 'L')) => {
+                let direction = match c {
+                    'H' => (-1.0, 0.0),
+                    'J' => (0.0, -1.0),
+                    'K' => (0.0, 1.0),
+                    _ => (1.0, 0.0),
+                };
+                if let Some(next) = self.selected_region.and_then(|i| {
+                    ctx.location
+                        .adjacency
+                        .towards(&ctx.location.regions, i, direction)
+                }) {
+                    self.selected_region = Some(next);
+                }
+                return (vec![], vec![]);
+            }
+            Message::Up | Message::Down if self.focus == ListFocus::Pois => {
+                let rows = poi_rows(&poi_collections(&ctx)).len();
+                self.step_poi(matches!(msg, Message::Down), rows);
+                return (vec![], vec![]);
+            }
             Message::Activated => {
                 self.map
                     .fit_polygons(&ctx.location.boundaries, &ctx.location.regions);
@@ -94,7 +610,7 @@ impl Component for InspectScreen {
             }
             Message::Left => {
                 let layer_ids: Vec<String> = std::iter::once(DEFAULT_LAYER_ID.to_string())
-                    .chain(ctx.configured_layers.iter().map(|s| s.id.to_string()))
+                    .chain(value_layers(&ctx).map(|s| s.id.to_string()))
                     .collect();
 
                 let layer_idx = layer_ids
@@ -115,7 +631,7 @@ impl Component for InspectScreen {
             }
             Message::Right => {
                 let layer_ids: Vec<String> = std::iter::once(DEFAULT_LAYER_ID.to_string())
-                    .chain(ctx.configured_layers.iter().map(|s| s.id.to_string()))
+                    .chain(value_layers(&ctx).map(|s| s.id.to_string()))
                     .collect();
 
                 let layer_idx = layer_ids
@@ -140,13 +656,36 @@ impl Component for InspectScreen {
             center: &ORIGIN,
             boundaries: &ctx.location.boundaries,
             regions: &ctx.location.regions,
+            region_index: Some(&ctx.location.index),
+            levels: Some(&ctx.location.levels),
+            generation: ctx.location.generation,
             polylines: &[],
             points: &[],
+            markers: &[],
             title: &ctx.location.tag.name,
             selected_region: &self.selected_region,
+            neighbours: self.selected_neighbours(ctx.location),
             fill_info: None,
+            origin: Some(&ctx.location.latlng),
+            theme: ctx.theme,
         };
-        self.map.update(msg, map_ctx, db)
+        let out = self.map.update(msg, map_ctx, db);
+        // A click selects the region under it, or the nearest one within a
+        // cell so thin gaps between regions still hit something.
+        if let (Message::Mouse { click: true, .. }, Some(at), None) =
+            (msg, self.map.cursor(), self.map.measurement())
+        {
+            let (cell_x, cell_y) = self.map.cell_size();
+            let index = &ctx.location.index;
+            let regions = &ctx.location.regions;
+            self.selected_region = index.at(regions, at.x, at.y).or_else(|| {
+                index
+                    .nearest(regions, at.x, at.y)
+                    .filter(|(_, distance)| *distance <= cell_x.max(cell_y))
+                    .map(|(i, _)| i)
+            });
+        }
+        out
     }
 
     fn render<'a>(&self, frame: &mut Frame, area: Rect, ctx: InspectScreenCtx<'a>) {
@@ -155,49 +694,106 @@ impl Component for InspectScreen {
             .constraints([Constraint::Min(30), Constraint::Percentage(100)])
             .split(area);
 
-        let layer_fills = match ctx.layers.get(ctx.active_layer) {
-            Some(LayerState::Loading) => None,
-            Some(LayerState::Failed(_)) => None,
-            Some(LayerState::Loaded(values)) => Some(FillByValue {
-                map: ColorMap::magma(),
-                values: values.clone(),
-            }),
-            None => None,
-        };
+        let title = map_title(&ctx);
+        let collections = poi_collections(&ctx);
+        let rows = poi_rows(&collections);
+        let markers = self.poi_markers(&collections, &ctx.theme);
+        let line_collections = line_collections(&ctx);
+        let lines = self.map_lines(&line_collections, &ctx.theme);
         let map_ctx = MapViewCtx {
             center: &ORIGIN,
             boundaries: &ctx.location.boundaries,
             regions: &ctx.location.regions,
-            polylines: &[],
+            region_index: Some(&ctx.location.index),
+            levels: Some(&ctx.location.levels),
+            generation: ctx.location.generation,
+            polylines: &lines,
             points: &[],
-            title: &ctx.location.tag.name,
+            markers: &markers,
+            title: &title,
             selected_region: &self.selected_region,
-            fill_info: layer_fills,
+            neighbours: self.selected_neighbours(ctx.location),
+            fill_info: layer_fills(&ctx),
+            origin: Some(&ctx.location.latlng),
+            theme: ctx.theme,
         };
         self.map.render(frame, layout[1], map_ctx);
+        if let Some((prompt, _)) = &self.export_prompt {
+            prompt.render(frame, layout[1], &ctx.theme);
+        } else if let Some(PoiRow::Poi(collection, poi)) = self.selected_poi_row(&rows) {
+            render_poi_details(frame, layout[1], collection, poi, &ctx.theme);
+        }
 
+        // The POI list only takes room when there could be something in it.
+        let show_pois = !collections.is_empty()
+            || ctx
+                .configured_layers
+                .iter()
+                .any(|l| l.kind == LayerKind::Pois);
+        let constraints = if show_pois {
+            vec![
+                Constraint::Percentage(30),
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+            ]
+        } else {
+            vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+        };
         let controls_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .constraints(constraints)
             .split(layout[0]);
+        if show_pois {
+            self.render_poi_list(
+                frame,
+                controls_layout[2],
+                &collections,
+                &rows,
+                pending_layers(&ctx, LayerKind::Pois),
+                &ctx.theme,
+            );
+        }
 
-        let layers_block = Block::bordered().title("Layers");
+        let values = region_values(&ctx);
+        let mut layers_block = Block::bordered().title("Layers (1-9 lines)");
+        if let Some(i) = values
+            .as_ref()
+            .and_then(|v| ctx.location.adjacency.morans_i(v))
+        {
+            layers_block = layers_block.title_bottom(format!("Moran's I {:.2}", i));
+        }
         frame.render_widget(&layers_block, controls_layout[0]);
         let mut layers_list_state = ListState::default();
         let layer_labels: Vec<String> = std::iter::once("Boundaries".to_string())
-            .chain(ctx.configured_layers.iter().map(|s| {
+            .chain(value_layers(&ctx).map(|s| {
                 let layer_status = match ctx.layers.get(&s.id) {
                     Some(LayerState::Loading) => "Loading".to_string(),
-                    Some(LayerState::Loaded(_)) => "Loaded!".to_string(),
-                    Some(LayerState::Failed(err)) => format!("Failed - {}", err),
+                    Some(
+                        LayerState::Loaded(_)
+                        | LayerState::LoadedPois(_)
+                        | LayerState::LoadedLines(_),
+                    ) => "Loaded!".to_string(),
+                    Some(LayerState::Failed(err)) => {
+                        format!("Failed - {}", ErrorReport::new(err).one_line())
+                    }
                     None => "Not Triggered".to_string(),
                 };
                 format!("{} - {}", s.name, layer_status)
             }))
+            // Line collections follow, toggled by their number.
+            .chain(line_collections.iter().enumerate().map(|(i, c)| {
+                let shown = if self.hidden_lines.contains(&c.id) {
+                    ' '
+                } else {
+                    'x'
+                };
+                format!("{} [{}] {}", i + 1, shown, c.name)
+            }))
+            .chain(pending_layers(&ctx, LayerKind::Lines))
             .collect();
 
         let layer_ids: Vec<String> = std::iter::once(DEFAULT_LAYER_ID.to_string())
-            .chain(ctx.configured_layers.iter().map(|s| s.id.to_string()))
+            .chain(value_layers(&ctx).map(|s| s.id.to_string()))
             .collect();
         let layers_list = List::new(layer_labels)
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
@@ -211,7 +807,18 @@ impl Component for InspectScreen {
             &mut layers_list_state,
         );
 
-        let regions_block = Block::bordered().title("Regions");
+        let mut regions_block = Block::bordered()
+            .title("Regions (HJKL neighbours)")
+            .border_style(self.flash.style(CopiedField::Table, &ctx.theme));
+        if let Some(status) = self.flash.status(&ctx.theme) {
+            regions_block = regions_block.title_bottom(status);
+        } else if let Some(mean) = self
+            .selected_region
+            .zip(values.as_ref())
+            .and_then(|(i, v)| ctx.location.adjacency.neighbour_mean(i, v))
+        {
+            regions_block = regions_block.title_bottom(format!("Neighbours' mean {:.2}", mean));
+        }
         frame.render_widget(&regions_block, controls_layout[1]);
         let mut region_list_state = ListState::default();
 
@@ -219,7 +826,11 @@ impl Component for InspectScreen {
             .chain(ctx.location.regions.iter().map(|p| {
                 let region_layer_val = match ctx.layers.get(ctx.active_layer) {
                     Some(LayerState::Loading) => "Loading".to_string(),
-                    Some(LayerState::Failed(_)) => "".to_string(),
+                    Some(
+                        LayerState::Failed(_)
+                        | LayerState::LoadedPois(_)
+                        | LayerState::LoadedLines(_),
+                    ) => "".to_string(),
                     Some(LayerState::Loaded(values)) => match values.get(&p.metadata.id) {
                         Some(v) => format!("{:.2}", v),
                         None => "-".to_string(),
@@ -229,8 +840,20 @@ impl Component for InspectScreen {
                 format!("{} - {}", p.metadata.name.clone(), region_layer_val)
             }))
             .collect();
+        // The selected row flashes when something about its region is copied.
+        let region_highlight = [
+            CopiedField::RegionId,
+            CopiedField::RegionName,
+            CopiedField::RegionValue,
+            CopiedField::GeoJson,
+            CopiedField::Wkt,
+        ]
+        .into_iter()
+        .map(|f| self.flash.style(f, &ctx.theme))
+        .find(|s| *s != Style::new())
+        .unwrap_or(Style::default().add_modifier(Modifier::REVERSED));
         let region_list = List::new(region_labels.iter().map(|s| s.as_str()))
-            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_style(region_highlight)
             .highlight_symbol("▶ ");
 
         match self.selected_region {
@@ -248,3 +871,42 @@ impl Component for InspectScreen {
         );
     }
 }
+
+/// A selected POI's collection, position and attributes, over the bottom
+/// of the map.
+fn render_poi_details(
+    frame: &mut Frame,
+    area: Rect,
+    collection: &PoiCollection,
+    poi: &Poi,
+    theme: &Theme,
+) {
+    let key = |k: &str| Span::styled(format!("{}: ", k), Style::new().fg(theme.accent));
+    let mut lines = vec![
+        Line::from(vec![
+            key("collection"),
+            Span::raw(format!("{} ({})", collection.name, collection.category)),
+        ]),
+        Line::from(vec![key("id"), Span::raw(poi.id.clone())]),
+        Line::from(vec![
+            key("at"),
+            Span::raw(format!("{:.0}, {:.0} m", poi.point.x, poi.point.y)),
+        ]),
+    ];
+    for (name, value) in &poi.attributes {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        lines.push(Line::from(vec![key(name), Span::raw(value)]));
+    }
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let [popup] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::End)
+        .areas(area);
+    let block = Block::bordered()
+        .title(poi.name.as_str())
+        .border_style(Style::new().fg(theme.selection));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
@@ -2,20 +2,51 @@ use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs},
     Frame,
 };
 
 use crate::{
-    coastlines::coastlines, component::Component, components::map_view::{MapView, MapViewCtx}, db::{db_connection::DBConnection, file_db::FileDB}, domain::{geometry::{Point, WGS84}, location::LocationTag}, message::Message, model::Model, update::Update
+    coastlines::coastlines, component::Component, components::map_view::{MapView, MapViewCtx}, db::{db_connection::DBConnection, file_db::FileDB, validation::{Diagnostic, Severity}}, domain::{geometry::{Point, WGS84}, location::LocationTag}, error::ErrorReport, message::Message, model::Model, theme::Theme, update::Update
 };
 
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum ListTab {
+    All,
+    Starred,
+    Recent,
+}
+
+impl ListTab {
+    const ALL: [ListTab; 3] = [ListTab::All, ListTab::Starred, ListTab::Recent];
+
+    fn title(&self) -> &'static str {
+        match self {
+            ListTab::All => "All",
+            ListTab::Starred => "Starred",
+            ListTab::Recent => "Recent",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            ListTab::All => ListTab::Starred,
+            ListTab::Starred => ListTab::Recent,
+            ListTab::Recent => ListTab::All,
+        }
+    }
+}
+
 pub struct LocationSelectScreen {
     idx: usize,
+    tab: ListTab,
     location_tags: Vec<LocationTag>,
     query: Option<String>,
     matcher: SkimMatcherV2,
     matches: Vec<usize>,
+    /// Dataset validation results, shown in place of the map while `Some`.
+    diagnostics: Option<Vec<Diagnostic>>,
     pub map: MapView<WGS84>,
 }
 
@@ -25,11 +56,13 @@ impl LocationSelectScreen {
         let n_tags = tags.len();
         Self {
             idx: 0,
+            tab: ListTab::All,
             location_tags: tags,
             query: None,
             matcher: SkimMatcherV2::default(),
             matches: (0..n_tags).collect(),
-            map: MapView::new(coastlines(), Some(1.0), true, false)
+            diagnostics: None,
+            map: MapView::new(Some(coastlines()), Some(1.0), true, false)
         }
     }
 
@@ -37,20 +70,79 @@ impl LocationSelectScreen {
         self.idx = self.idx.saturating_sub(1);
     }
 
-    fn move_down(&mut self) {
-        if self.idx < self.matches.len().saturating_sub(1) {
+    fn move_down(&mut self, n_visible: usize) {
+        if self.idx < n_visible.saturating_sub(1) {
             self.idx += 1;
         }
     }
 
+    /// Indices into `location_tags` shown under the current tab: the search
+    /// matches, narrowed to starred or recent locations. The Recent tab keeps
+    /// most-recent-first order rather than match order.
+    fn visible(&self, model: &Model) -> Vec<usize> {
+        match self.tab {
+            ListTab::All => self.matches.clone(),
+            ListTab::Starred => self
+                .matches
+                .iter()
+                .copied()
+                .filter(|&i| model.saved_state.is_starred(&self.location_tags[i].id))
+                .collect(),
+            ListTab::Recent => model
+                .saved_state
+                .recent
+                .iter()
+                .filter_map(|id| {
+                    self.matches
+                        .iter()
+                        .copied()
+                        .find(|&i| &self.location_tags[i].id == id)
+                })
+                .collect(),
+        }
+    }
+
+    fn toggle_star(&self, visible: &[usize]) -> (Vec<Update>, Vec<Message>) {
+        match visible.get(self.idx) {
+            Some(&i) => (
+                vec![Update::ToggleStar {
+                    location_id: self.location_tags[i].id.clone(),
+                }],
+                vec![],
+            ),
+            None => (vec![], vec![]),
+        }
+    }
+
+    /// Swap in the tags of a reloaded dataset, keeping the search, tab and
+    /// highlighted location (if it still exists).
+    pub fn reload(&mut self, db: &FileDB, model: &Model) {
+        let highlighted = self
+            .visible(model)
+            .get(self.idx)
+            .map(|&i| self.location_tags[i].id.clone());
+        self.location_tags = db.get_tags();
+        self.matches = recompute_matches(&self.query, &self.location_tags, &self.matcher);
+        let visible = self.visible(model);
+        self.idx = highlighted
+            .and_then(|id| {
+                visible
+                    .iter()
+                    .position(|&i| self.location_tags[i].id == id)
+            })
+            .unwrap_or(0);
+        if self.diagnostics.is_some() {
+            self.diagnostics = Some(db.validate());
+        }
+    }
+
     fn refresh_matches(&mut self) {
         self.matches = recompute_matches(&self.query, &self.location_tags, &self.matcher);
         self.idx = 0;
     }
 
-    fn select(&mut self, db: &FileDB) -> (Vec<Update>, Vec<Message>) {
-        let items: Vec<&LocationTag> = self
-            .matches
+    fn select(&mut self, visible: &[usize], db: &FileDB) -> (Vec<Update>, Vec<Message>) {
+        let items: Vec<&LocationTag> = visible
             .iter()
             .map(|&i| &self.location_tags[i])
             .collect();
@@ -58,17 +150,16 @@ impl LocationSelectScreen {
         let selected_item = items.get(self.idx);
         if let Some(selected_tag) = selected_item {
             match db.get_by_id(&selected_tag.id) {
-                Some(loc) => {
+                Ok(loc) => {
                     self.query = None;
                     self.matches = (0..self.location_tags.len()).collect();
                     (vec![Update::SetLocation(loc)], vec![])
                 }
-                None => (
-                    vec![Update::SetError(
-                        "Location not able to be loaded".to_string(),
-                    )],
-                    vec![],
-                ),
+                Err(e) => {
+                    let report = ErrorReport::new(&e)
+                        .with_hint("press 'v' on the location list for diagnostics");
+                    (vec![Update::SetError(report)], vec![])
+                }
             }
         } else {
             (vec![], vec![])
@@ -82,9 +173,17 @@ impl Component for LocationSelectScreen {
     fn update(
         &mut self,
         msg: &Message,
-        _model: &Model,
+        model: &Model,
         db: &FileDB,
     ) -> (Vec<Update>, Vec<Message>) {
+        let visible = self.visible(model);
+        // The starred list can shrink underneath us (unstarring on the Starred tab).
+        self.idx = self.idx.min(visible.len().saturating_sub(1));
+        if let Message::Tab = msg {
+            self.tab = self.tab.next();
+            self.idx = 0;
+            return (vec![], vec![]);
+        }
         if let Some(query) = &mut self.query {
             // search mode — chars feed the query, arrows navigate, esc exits
             match msg {
@@ -101,20 +200,32 @@ impl Component for LocationSelectScreen {
                     self.refresh_matches();
                 }
                 Message::Up => self.move_up(),
-                Message::Down => self.move_down(),
+                Message::Down => self.move_down(visible.len()),
                 Message::Enter => {
-                    return self.select(db);
+                    return self.select(&visible, db);
                 }
                 _ => {}
             }
         } else {
-            // nav mode — wasd/vim/arrows navigate, '/' enters search
+            // nav mode — wasd/vim/arrows navigate, '/' enters search, '*' stars
             match msg {
                 Message::Up | Message::Char('w') | Message::Char('k') => self.move_up(),
-                Message::Down | Message::Char('s') | Message::Char('j') => self.move_down(),
+                Message::Down | Message::Char('s') | Message::Char('j') => {
+                    self.move_down(visible.len())
+                }
                 Message::Char('/') => self.query = Some(String::new()),
+                Message::Char('*') => return self.toggle_star(&visible),
+                Message::Char('v') => {
+                    self.diagnostics = match self.diagnostics {
+                        Some(_) => None,
+                        None => Some(db.validate()),
+                    };
+                }
+                Message::Char('r') if model.restorable_session.is_some() => {
+                    return (vec![], vec![Message::RestoreSession]);
+                }
                 Message::Enter => {
-                    return self.select(db);
+                    return self.select(&visible, db);
                 }
                 _ => {}
             }
@@ -128,13 +239,19 @@ impl Component for LocationSelectScreen {
             .constraints([Constraint::Min(1), Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
             .split(area);
 
-        let items: Vec<ListItem> = self
-            .matches
+        let visible = self.visible(ctx);
+        let items: Vec<ListItem> = visible
             .iter()
             .map(|&i| {
                 let tag = &self.location_tags[i];
+                let star = if ctx.saved_state.is_starred(&tag.id) {
+                    "★"
+                } else {
+                    " "
+                };
                 ListItem::new(format!(
-                    "{} - {}  [{}/{}]  {} · {}",
+                    "{} {} - {}  [{}/{}]  {} · {}",
+                    star,
                     tag.id,
                     tag.name,
                     tag.country_code,
@@ -152,29 +269,95 @@ impl Component for LocationSelectScreen {
         list_state.select(Some(self.idx));
         frame.render_stateful_widget(list, layout[1], &mut list_state);
 
-        let p = match &self.query {
-            Some(q) => Paragraph::new(format!("/ {}", q)),
-            None => Paragraph::new("Press '/' to search"),
+        let header_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(100), Constraint::Min(30)])
+            .split(layout[0]);
+        let p = match (&self.query, &ctx.restorable_session) {
+            (Some(q), _) => Paragraph::new(format!("/ {}", q)),
+            (None, Some(session)) => Paragraph::new(format!(
+                "Press 'r' to restore last session ({}), '/' to search, '*' to star",
+                session.location_id
+            )),
+            (None, None) => Paragraph::new("Press '/' to search, '*' to star, 'v' to validate"),
         };
-        frame.render_widget(p, layout[0]);
+        frame.render_widget(p, header_layout[0]);
+        let tabs = Tabs::new(ListTab::ALL.iter().map(|t| t.title()))
+            .select(ListTab::ALL.iter().position(|t| *t == self.tab))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(tabs, header_layout[1]);
 
-        let points: Vec<Point<WGS84>> = self.matches.iter().map(|&i| self.location_tags[i].coord).collect();
+        let points: Vec<Point<WGS84>> = visible.iter().map(|&i| self.location_tags[i].coord).collect();
+        let theme = ctx.config.theme();
 
+        if let Some(diagnostics) = &self.diagnostics {
+            render_diagnostics(frame, layout[2], diagnostics, &theme);
+            return;
+        }
         let map_ctx = MapViewCtx {
-            center: &self.location_tags[self.matches.get(self.idx).copied().unwrap_or(0)].coord,
+            center: &self.location_tags[visible.get(self.idx).copied().unwrap_or(0)].coord,
             boundaries: &[],
             regions: &[],
+            region_index: None,
+            levels: None,
+            generation: 0,
             polylines: &[],
             points: &points,
+            markers: &[],
             title: "None",
             selected_region: &None,
+            neighbours: &[],
             fill_info: None,
+            origin: None,
+            theme,
         };
         self.map.render(frame, layout[2], map_ctx);
     }
 }
 
-fn recompute_matches(
+fn render_diagnostics(frame: &mut Frame, area: Rect, diagnostics: &[Diagnostic], theme: &Theme) {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let title = format!(
+        "Diagnostics - {} error(s), {} warning(s) ('v' to close)",
+        errors,
+        diagnostics.len() - errors
+    );
+    let items: Vec<ListItem> = if diagnostics.is_empty() {
+        vec![ListItem::new("No problems found")]
+    } else {
+        diagnostics
+            .iter()
+            .map(|d| {
+                let (label, color) = match d.severity {
+                    Severity::Error => ("error  ", theme.error),
+                    Severity::Warning => ("warning", theme.warning),
+                };
+                let region = d
+                    .region_id
+                    .as_ref()
+                    .map(|r| format!("/{}", r))
+                    .unwrap_or_default();
+                let line = d.line.map(|l| format!(":{}", l)).unwrap_or_default();
+                ListItem::new(Line::from(vec![
+                    Span::styled(label, Style::new().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" [{}{}] ", d.location_id, region)),
+                    Span::raw(d.message.clone()),
+                    Span::styled(
+                        format!("  {}{}", d.file.display(), line),
+                        Style::new().add_modifier(Modifier::DIM),
+                    ),
+                ]))
+            })
+            .collect()
+    };
+    let list = List::new(items).block(Block::default().borders(Borders::all()).title(title));
+    frame.render_widget(list, area);
+}
+
+pub fn recompute_matches(
     query: &Option<String>,
     tags: &[LocationTag],
     matcher: &SkimMatcherV2,
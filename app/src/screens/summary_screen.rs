@@ -1,24 +1,32 @@
-use std::time::{Duration, Instant};
+use std::error::Error;
 
-use arboard::Clipboard;
 use ratatui::{
-    Frame, layout::{Constraint, Direction, Layout, Rect}, style::{Color, Modifier, Style}, text::{Line, Span}, widgets::{Block, Paragraph}
+    Frame, layout::{Constraint, Direction, Layout, Rect}, style::{Modifier, Style}, text::{Line, Span}, widgets::{Block, Paragraph}
 };
 
 use crate::{
+    clipboard::ClipboardMethod,
     coastlines::coastlines,
     component::Component,
-    components::map_view::{MapView, MapViewCtx},
+    components::{
+        copy_flash::CopyFlash,
+        map_view::{MapView, MapViewCtx},
+        path_prompt::{PathPrompt, PromptOutcome},
+    },
     db::file_db::FileDB,
     domain::{geometry::WGS84, location::Location},
+    export::{self, MapFormat},
     message::Message,
     model::InspectingLocationView,
+    theme::Theme,
     update::Update,
 };
 
 pub struct SummaryScreenCtx<'a> {
     pub location: &'a Location,
-    pub err: &'a Option<String>,
+    pub starred: bool,
+    pub clipboard: ClipboardMethod,
+    pub theme: Theme,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -27,38 +35,46 @@ enum CopiedField {
     Name,
     Country,
     Coord,
+    Record,
+    Measurement,
 }
 
-const FLASH_DURATION: Duration = Duration::from_millis(300);
-
 pub struct SummaryScreen {
     pub map: MapView<WGS84>,
-    flash: Option<(CopiedField, Instant)>,
+    flash: CopyFlash<CopiedField>,
+    /// Output file being chosen for a map export, while the prompt is open.
+    export_prompt: Option<(PathPrompt, MapFormat)>,
 }
 
 impl SummaryScreen {
     pub fn new() -> Self {
         Self {
-            map: MapView::new(coastlines(), Some(0.1), true, true),
-            flash: None,
-        }
-    }
-
-    fn copy(&mut self, field: CopiedField, value: &str) {
-        if let Ok(mut cb) = Clipboard::new() {
-            if cb.set_text(value).is_ok() {
-                self.flash = Some((field, Instant::now() + FLASH_DURATION));
-            }
+            map: MapView::new(Some(coastlines()), Some(0.1), true, true),
+            flash: CopyFlash::new(),
+            export_prompt: None,
         }
     }
 
-    fn flash_style(&self, field: CopiedField) -> Style {
-        match self.flash {
-            Some((f, until)) if f == field && Instant::now() < until => {
-                Style::new().bg(Color::Green).fg(Color::Black)
-            }
-            _ => Style::new(),
-        }
+    /// The map as last drawn: the location marker over the coastlines.
+    pub fn map_svg(&self, ctx: &SummaryScreenCtx) -> Result<String, Box<dyn Error>> {
+        let map_ctx = MapViewCtx {
+            center: &ctx.location.latlng,
+            boundaries: &[],
+            regions: &[],
+            region_index: None,
+            levels: None,
+            generation: 0,
+            polylines: &[],
+            points: &[],
+            markers: &[],
+            title: &ctx.location.tag.name,
+            selected_region: &None,
+            neighbours: &[],
+            fill_info: None,
+            origin: None,
+            theme: ctx.theme,
+        };
+        export::map_svg(&self.map, &map_ctx)
     }
 }
 
@@ -70,7 +86,25 @@ impl Component for SummaryScreen {
         ctx: SummaryScreenCtx,
         db: &FileDB,
     ) -> (Vec<Update>, Vec<Message>) {
+        if let Some((prompt, format)) = &mut self.export_prompt {
+            let format = *format;
+            return match prompt.update(msg) {
+                PromptOutcome::Editing => (vec![], vec![]),
+                PromptOutcome::Cancelled => {
+                    self.export_prompt = None;
+                    (vec![], vec![])
+                }
+                PromptOutcome::Submitted(path) => {
+                    self.export_prompt = None;
+                    export::export_map(path, self.map_svg(&ctx), format)
+                }
+            };
+        }
         match msg {
+            Message::Esc if self.map.cursor().is_some() || self.map.measurement().is_some() => {
+                self.map.hide_cursor();
+                return (vec![], vec![]);
+            }
             Message::Esc => return (vec![Update::ClearLocation], vec![]),
             Message::Tab => {
                 return (
@@ -80,12 +114,30 @@ impl Component for SummaryScreen {
                     vec![Message::Activated, Message::LoadLayers],
                 )
             }
+            Message::Char('*') => {
+                return (
+                    vec![Update::ToggleStar {
+                        location_id: ctx.location.tag.id.clone(),
+                    }],
+                    vec![],
+                )
+            }
+            Message::Char('S') | Message::Char('P') => {
+                let format = match msg {
+                    Message::Char('S') => MapFormat::Svg,
+                    _ => MapFormat::Png,
+                };
+                let path = format!("{}-summary.{}", ctx.location.tag.id, format.extension());
+                let title = format!("Export map as {}", format.extension().to_uppercase());
+                self.export_prompt = Some((PathPrompt::new(title, path), format));
+                return (vec![], vec![]);
+            }
             Message::Char('i') => {
-                self.copy(CopiedField::Id, &ctx.location.tag.id);
+                self.flash.copy(CopiedField::Id, &ctx.location.tag.id, ctx.clipboard);
                 return (vec![], vec![]);
             }
             Message::Char('n') => {
-                self.copy(CopiedField::Name, &ctx.location.tag.name);
+                self.flash.copy(CopiedField::Name, &ctx.location.tag.name, ctx.clipboard);
                 return (vec![], vec![]);
             }
             Message::Char('c') => {
@@ -93,13 +145,25 @@ impl Component for SummaryScreen {
                     "{}/{}",
                     ctx.location.tag.country_code, ctx.location.tag.country_subdivision
                 );
-                self.copy(CopiedField::Country, &value);
+                self.flash.copy(CopiedField::Country, &value, ctx.clipboard);
+                return (vec![], vec![]);
+            }
+            Message::Char('J') => {
+                let value = format!("{:#}", ctx.location.to_json(ctx.starred));
+                self.flash.copy(CopiedField::Record, &value, ctx.clipboard);
+                return (vec![], vec![]);
+            }
+            Message::Char('y') => {
+                if let Some(measurement) = self.map.measurement() {
+                    let value = measurement.to_string();
+                    self.flash.copy(CopiedField::Measurement, &value, ctx.clipboard);
+                }
                 return (vec![], vec![]);
             }
             Message::Char('l') => {
                 let coord = &ctx.location.tag.coord;
                 let value = format!("{:.4}, {:.4}", coord.x, coord.y);
-                self.copy(CopiedField::Coord, &value);
+                self.flash.copy(CopiedField::Coord, &value, ctx.clipboard);
                 return (vec![], vec![]);
             }
             _ => (),
@@ -108,11 +172,18 @@ impl Component for SummaryScreen {
             center: &ctx.location.latlng,
             boundaries: &[],
             regions: &[],
+            region_index: None,
+            levels: None,
+            generation: 0,
             polylines: &[],
             points: &[],
+            markers: &[],
             title: &ctx.location.tag.name,
             selected_region: &None,
+            neighbours: &[],
             fill_info: None,
+            origin: None,
+            theme: ctx.theme,
         };
         self.map.update(msg, map_ctx, db)
     }
@@ -127,42 +198,51 @@ impl Component for SummaryScreen {
             center: &ctx.location.latlng,
             boundaries: &[],
             regions: &[],
+            region_index: None,
+            levels: None,
+            generation: 0,
             polylines: &[],
             points: &[],
+            markers: &[],
             title: "None",
             selected_region: &None,
+            neighbours: &[],
             fill_info: None,
+            origin: None,
+            theme: ctx.theme,
         };
         self.map.render(frame, layout[1], map_ctx);
+        if let Some((prompt, _)) = &self.export_prompt {
+            prompt.render(frame, layout[1], &ctx.theme);
+        }
 
-        let summary_block = Block::bordered().title("Summary");
+        let mut summary_block = Block::bordered().title("Summary");
+        if let Some(status) = self.flash.status(&ctx.theme) {
+            summary_block = summary_block.title_bottom(status);
+        }
         frame.render_widget(&summary_block, layout[0]);
-        let err_str = match &ctx.err {
-            Some(err) => format!(" - {}", err),
-            _ => String::new(),
-        };
 
         let tag = &ctx.location.tag;
           let dim = Style::new().add_modifier(Modifier::DIM);
-          let key = Style::new().yellow();
+          let key = Style::new().fg(ctx.theme.accent);
 
           let lines = vec![
               Line::from(vec![
                   Span::styled("[i]", key), Span::raw(" "),
                   Span::styled("ID:       ", dim),
-                  Span::styled(&tag.id, self.flash_style(CopiedField::Id)),
+                  Span::styled(&tag.id, self.flash.style(CopiedField::Id, &ctx.theme)),
               ]),
               Line::from(vec![
                   Span::styled("[n]", key), Span::raw(" "),
                   Span::styled("Name:     ", dim),
-                  Span::styled(&tag.name, self.flash_style(CopiedField::Name)),
+                  Span::styled(&tag.name, self.flash.style(CopiedField::Name, &ctx.theme)),
               ]),
               Line::from(vec![
                   Span::styled("[c]", key), Span::raw(" "),
                   Span::styled("Country:  ", dim),
                   Span::styled(
                       format!("{}/{}", tag.country_code, tag.country_subdivision),
-                      self.flash_style(CopiedField::Country),
+                      self.flash.style(CopiedField::Country, &ctx.theme),
                   ),
               ]),
               Line::from(vec![
@@ -183,9 +263,19 @@ impl Component for SummaryScreen {
                   Span::styled("Coord:    ", dim),
                   Span::styled(
                       format!("{:.4}, {:.4}", tag.coord.x, tag.coord.y),
-                      self.flash_style(CopiedField::Coord),
+                      self.flash.style(CopiedField::Coord, &ctx.theme),
                   ),
               ]),
+              Line::from(vec![
+                  Span::styled("[*]", key), Span::raw(" "),
+                  Span::styled("Starred:  ", dim),
+                  Span::raw(if ctx.starred { "★" } else { "-" }),
+              ]),
+              Line::from(vec![
+                  Span::styled("[J]", key), Span::raw(" "),
+                  Span::styled("Record:   ", dim),
+                  Span::styled("copy as JSON", self.flash.style(CopiedField::Record, &ctx.theme)),
+              ]),
           ];
 
         let p = Paragraph::new(lines);
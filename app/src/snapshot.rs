@@ -0,0 +1,228 @@
+use std::{
+    error::Error,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, style::Modifier, Terminal};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    app::App,
+    color::{hex, rgb},
+    config::Config,
+    db::file_db::FileDB,
+    message::Message,
+    model::{InteractionMode, LayerState},
+    state::{SavedState, Session},
+    update::Update,
+};
+
+/// Build the same `App` the TUI runs, open `session` (the location list if
+/// `None`) and wait for its layers to finish loading.
+pub fn headless_app(
+    db: FileDB,
+    config: Config,
+    session: Option<Session>,
+) -> Result<App, Box<dyn Error>> {
+    let layer_wait = config
+        .layers
+        .iter()
+        .map(|l| Duration::from_secs(l.timeout_secs))
+        .max()
+        .unwrap_or_default()
+        + Duration::from_secs(1);
+    let (async_tx, async_rx) = mpsc::channel::<Update>();
+    let mut app = App::new(db, config, SavedState::load().read_only(), async_tx);
+    if let Some(session) = session {
+        app.model.restorable_session = Some(session);
+        app.handle(Message::RestoreSession);
+        if let Some(err) = &app.model.err {
+            return Err(err.to_string().into());
+        }
+    }
+
+    // Layer commands run on background threads; the timeouts bound this wait.
+    let deadline = Instant::now() + layer_wait;
+    while layers_loading(&app) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match async_rx.recv_timeout(remaining) {
+            Ok(u) => app.model.apply(u),
+            Err(_) => break,
+        }
+    }
+    Ok(app)
+}
+
+/// Draw a single frame of `app` into an off-screen buffer.
+pub fn render_buffer(app: &App, width: u16, height: u16) -> Result<Buffer, Box<dyn Error>> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|frame| app.render(frame))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+fn layers_loading(app: &App) -> bool {
+    match &app.model.interaction_mode {
+        InteractionMode::InspectingLocation { state } => state
+            .layers
+            .values()
+            .any(|s| matches!(s, LayerState::Loading)),
+        InteractionMode::BrowsingLocation => false,
+    }
+}
+
+/// Visible cells of one buffer row, skipping the cells hidden behind
+/// double-width symbols.
+fn row_cells(buffer: &Buffer, y: u16) -> impl Iterator<Item = &ratatui::buffer::Cell> {
+    let width = buffer.area.width as usize;
+    let row = &buffer.content[y as usize * width..(y as usize + 1) * width];
+    let mut skip = 0usize;
+    row.iter().filter(move |cell| {
+        let visible = skip == 0;
+        skip = skip.max(cell.symbol().width()).saturating_sub(1);
+        visible
+    })
+}
+
+pub fn to_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let line: String = row_cells(buffer, y).map(|c| c.symbol()).collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut current = None;
+        for cell in row_cells(buffer, y) {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Select Graphic Rendition sequence that resets, then applies this style.
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (m, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(m) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(ansi_color(fg, false));
+    codes.extend(ansi_color(bg, true));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn ansi_color(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(i) => return Some(format!("{};5;{}", base + 8, i)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    };
+    Some(code.to_string())
+}
+
+const HTML_FG: (u8, u8, u8) = (0xd0, 0xd0, 0xd0);
+const HTML_BG: (u8, u8, u8) = (0x10, 0x10, 0x10);
+
+pub fn to_html(buffer: &Buffer) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"></head>\n\
+         <body style=\"margin:0;background:{bg}\">\n\
+         <pre style=\"margin:0;padding:1em;color:{fg};background:{bg};\
+         font-family:monospace;line-height:1.2\">",
+        fg = hex(HTML_FG),
+        bg = hex(HTML_BG),
+    );
+    for y in 0..buffer.area.height {
+        let mut run_style = None;
+        let mut run = String::new();
+        for cell in row_cells(buffer, y) {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if run_style != Some(style) {
+                if let Some((fg, bg, m)) = run_style {
+                    out.push_str(&html_span(&run, fg, bg, m));
+                }
+                run.clear();
+                run_style = Some(style);
+            }
+            run.push_str(cell.symbol());
+        }
+        if let Some((fg, bg, m)) = run_style {
+            out.push_str(&html_span(run.trim_end(), fg, bg, m));
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n</body></html>\n");
+    out
+}
+
+fn html_span(text: &str, fg: Color, bg: Color, modifier: Modifier) -> String {
+    let escaped = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let (mut fg, mut bg) = (rgb(fg), rgb(bg));
+    if modifier.contains(Modifier::REVERSED) {
+        (fg, bg) = (Some(bg.unwrap_or(HTML_BG)), Some(fg.unwrap_or(HTML_FG)));
+    }
+    let mut css = vec![];
+    if let Some(c) = fg {
+        css.push(format!("color:{}", hex(c)));
+    }
+    if let Some(c) = bg {
+        css.push(format!("background:{}", hex(c)));
+    }
+    if modifier.contains(Modifier::BOLD) {
+        css.push("font-weight:bold".to_string());
+    }
+    if modifier.contains(Modifier::DIM) {
+        css.push("opacity:0.6".to_string());
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        css.push("font-style:italic".to_string());
+    }
+    if modifier.contains(Modifier::UNDERLINED) {
+        css.push("text-decoration:underline".to_string());
+    }
+    if css.is_empty() {
+        escaped
+    } else {
+        format!("<span style=\"{}\">{}</span>", css.join(";"), escaped)
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::error::StateError;
+
+const MAX_RECENT: usize = 20;
+
+/// User state that outlives a single session: starred locations and a
+/// most-recently-used list. Stored as JSON under the XDG data directory
+/// (`$XDG_DATA_HOME/loctui/state.json`, usually `~/.local/share/loctui`).
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct SavedState {
+    #[serde(default)]
+    pub starred: Vec<String>,
+    /// Most recent first.
+    #[serde(default)]
+    pub recent: Vec<String>,
+    /// Where the user was when they last quit, if they were inside a location.
+    #[serde(default)]
+    pub session: Option<Session>,
+    /// Set for headless runs so they never overwrite the user's state file.
+    #[serde(skip)]
+    read_only: bool,
+}
+
+/// Enough UI state to reopen a location exactly as it was left. Also built from
+/// startup flags, so the same state can be handed around as a command line.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct Session {
+    pub location_id: String,
+    pub view: SessionView,
+    #[serde(default)]
+    pub active_layer: Option<String>,
+    #[serde(default)]
+    pub selected_region: Option<String>,
+    #[serde(default)]
+    pub browse_map: Option<Viewport>,
+    #[serde(default)]
+    pub summary_map: Option<Viewport>,
+    #[serde(default)]
+    pub inspect_map: Option<Viewport>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionView {
+    Summary,
+    Inspect,
+}
+
+/// A `MapView` pan/zoom. `scale: None` keeps whatever scale the screen picks
+/// when it activates (e.g. fit-to-location), so flags can set just an offset.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy)]
+pub struct Viewport {
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub scale: Option<f64>,
+}
+
+impl SavedState {
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|d| d.join("loctui").join("state.json"))
+    }
+
+    /// Missing or unreadable state is treated as empty rather than fatal, so a
+    /// corrupt state file never stops the app from starting.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub fn save(&self) -> Result<(), StateError> {
+        if self.read_only {
+            return Ok(());
+        }
+        let path = Self::path().ok_or(StateError::NoDataDir)?;
+        let write = |path: &Path| -> io::Result<()> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, serde_json::to_string_pretty(self)?)
+        };
+        write(&path).map_err(|source| StateError::Write { path, source })
+    }
+
+    pub fn is_starred(&self, location_id: &str) -> bool {
+        self.starred.iter().any(|id| id == location_id)
+    }
+
+    pub fn toggle_star(&mut self, location_id: &str) {
+        if self.is_starred(location_id) {
+            self.starred.retain(|id| id != location_id);
+        } else {
+            self.starred.push(location_id.to_string());
+        }
+    }
+
+    pub fn push_recent(&mut self, location_id: &str) {
+        self.recent.retain(|id| id != location_id);
+        self.recent.insert(0, location_id.to_string());
+        self.recent.truncate(MAX_RECENT);
+    }
+}
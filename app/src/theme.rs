@@ -0,0 +1,196 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use ratatui::style::Color;
+
+use crate::glyphs::GlyphSet;
+
+/// Built-in colour schemes, picked with `theme.preset`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    /// Named terminal colours, for dark backgrounds.
+    #[default]
+    Dark,
+    /// Darker, saturated colours that stay readable on white.
+    Light,
+    /// Bright colours only, on black.
+    HighContrast,
+}
+
+/// How regions with a layer value are filled, set by `ui.fill_style`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FillStyle {
+    /// Patterns when the terminal has 16 colours or fewer, colour otherwise.
+    #[default]
+    Auto,
+    /// Solid colour from the layer's colour map.
+    Color,
+    /// Dot density shows the value and a hatch marks the selection, so both
+    /// read without telling colours apart.
+    Pattern,
+}
+
+/// A colour as written in the config: a name (`red`, `light-blue`), a
+/// 256-colour index (`208`) or `#rrggbb`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ThemeColor(pub Color);
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::from_str(s)
+            .map(ThemeColor)
+            .map_err(|_| format!("`{}` is not a colour name, 0-255 index or #rrggbb", s))
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ThemeColor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl serde::Serialize for ThemeColor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The `[theme]` section: a preset plus any per-element overrides, keyed by
+/// [`Theme`] field name.
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    #[serde(flatten)]
+    pub overrides: BTreeMap<String, ThemeColor>,
+}
+
+/// Every colour the UI draws with, resolved from a [`ThemeConfig`], plus
+/// whether fills use patterns and which characters draw points.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    /// Location boundary outlines.
+    pub boundary: Color,
+    /// Outlines of regions without a layer value.
+    pub region: Color,
+    pub polyline: Color,
+    /// The world coastline background.
+    pub coastline: Color,
+    /// The selected region's fill and outline.
+    pub selection: Color,
+    pub point: Color,
+    /// The `X` at the location's centre.
+    pub marker: Color,
+    /// Key hints, prompts and other things to draw the eye to.
+    pub accent: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
+    /// Background and text of map exports. The terminal keeps its own.
+    pub background: Color,
+    pub foreground: Color,
+    /// Fill regions by dot density rather than solid colour.
+    pub patterns: bool,
+    pub glyphs: GlyphSet,
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Theme {
+                boundary: Color::Red,
+                region: Color::Red,
+                polyline: Color::Red,
+                coastline: Color::Green,
+                selection: Color::Green,
+                point: Color::Rgb(255, 255, 255),
+                marker: Color::Red,
+                accent: Color::Yellow,
+                error: Color::Red,
+                warning: Color::Yellow,
+                success: Color::Green,
+                background: Color::Rgb(0x10, 0x10, 0x10),
+                foreground: Color::Rgb(0xd0, 0xd0, 0xd0),
+                patterns: false,
+                glyphs: GlyphSet::Auto,
+            },
+            ThemePreset::Light => Theme {
+                boundary: Color::Rgb(0xa0, 0x10, 0x10),
+                region: Color::Rgb(0xa0, 0x10, 0x10),
+                polyline: Color::Rgb(0x80, 0x40, 0x00),
+                coastline: Color::Rgb(0x1b, 0x6e, 0x20),
+                selection: Color::Rgb(0x00, 0x5f, 0xaf),
+                point: Color::Rgb(0x10, 0x10, 0x10),
+                marker: Color::Rgb(0xc0, 0x00, 0x00),
+                accent: Color::Rgb(0x00, 0x5f, 0xaf),
+                error: Color::Rgb(0xb0, 0x00, 0x00),
+                warning: Color::Rgb(0x9a, 0x5b, 0x00),
+                success: Color::Rgb(0x1b, 0x6e, 0x20),
+                background: Color::Rgb(0xfa, 0xfa, 0xfa),
+                foreground: Color::Rgb(0x20, 0x20, 0x20),
+                patterns: false,
+                glyphs: GlyphSet::Auto,
+            },
+            ThemePreset::HighContrast => Theme {
+                boundary: Color::LightRed,
+                region: Color::LightRed,
+                polyline: Color::LightMagenta,
+                coastline: Color::LightGreen,
+                selection: Color::LightCyan,
+                point: Color::White,
+                marker: Color::LightYellow,
+                accent: Color::LightYellow,
+                error: Color::LightRed,
+                warning: Color::LightYellow,
+                success: Color::LightGreen,
+                background: Color::Black,
+                foreground: Color::White,
+                patterns: false,
+                glyphs: GlyphSet::Auto,
+            },
+        }
+    }
+
+    fn element_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "boundary" => &mut self.boundary,
+            "region" => &mut self.region,
+            "polyline" => &mut self.polyline,
+            "coastline" => &mut self.coastline,
+            "selection" => &mut self.selection,
+            "point" => &mut self.point,
+            "marker" => &mut self.marker,
+            "accent" => &mut self.accent,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "success" => &mut self.success,
+            "background" => &mut self.background,
+            "foreground" => &mut self.foreground,
+            _ => return None,
+        })
+    }
+}
+
+impl ThemeConfig {
+    /// The preset with overrides applied. Unknown element names are caught by
+    /// config validation, so they are ignored here.
+    pub fn resolve(&self) -> Theme {
+        let mut theme = Theme::preset(self.preset);
+        for (name, color) in &self.overrides {
+            if let Some(slot) = theme.element_mut(name) {
+                *slot = color.0;
+            }
+        }
+        theme
+    }
+}
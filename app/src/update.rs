@@ -1,11 +1,21 @@
 use std::collections::HashMap;
 
-use crate::{domain::location::Location, model::InspectingLocationView};
+use crate::{
+    domain::{lines::LineCollection, location::Location, poi::PoiCollection},
+    error::{ErrorReport, LayerError},
+    model::InspectingLocationView,
+};
 
 pub enum Update {
     Quit,
-    SetError(String),
+    SetError(ErrorReport),
+    /// Short-lived confirmation shown in the corner, e.g. after an export.
+    Toast(String),
+    ToastError(String),
     SetLocation(Location),
+    /// A fresh copy of the open location after the dataset changed. Keeps
+    /// the screen and active layer but drops layer values.
+    ReloadLocation(Location),
     ClearLocation,
     SetInspectingLocationView(InspectingLocationView),
     TriggerLayerLoad {
@@ -17,12 +27,33 @@ pub enum Update {
         layer_id: String,
         layer_data: HashMap<String, f64>,
     },
+    SetLayerPois {
+        location_id: String,
+        layer_id: String,
+        collections: Vec<PoiCollection>,
+    },
+    SetLayerLines {
+        location_id: String,
+        layer_id: String,
+        collections: Vec<LineCollection>,
+    },
     SetLayerFailed {
         location_id: String,
         layer_id: String,
-        err_msg: String,
+        err: LayerError,
     },
     SetActiveLayer {
         layer_id: String,
     },
+    ToggleStar {
+        location_id: String,
+    },
+}
+
+impl Update {
+    /// Whether applying this update changes `Model.saved_state`, which must
+    /// then be written back to disk.
+    pub fn touches_saved_state(&self) -> bool {
+        matches!(self, Update::SetLocation(_) | Update::ToggleStar { .. })
+    }
 }
@@ -1,8 +1,13 @@
+use std::error::Error;
+
 use ratatui::{layout::Rect, Frame};
 
 use crate::{
     component::Component,
+    components::{error_popup::render_error_popup, toast::render_toast},
     db::file_db::FileDB,
+    domain::location::Location,
+    export::{self, MapFormat},
     message::Message,
     model::{InspectingLocationView, InspectingState, InteractionMode, Model},
     screens::{
@@ -10,6 +15,7 @@ use crate::{
         location_select_screen::LocationSelectScreen,
         summary_screen::{SummaryScreen, SummaryScreenCtx},
     },
+    state::{Session, SessionView},
     update::Update,
 };
 
@@ -27,6 +33,73 @@ impl View {
             inspect_screen: InspectScreen::new(),
         }
     }
+
+    /// Snapshot of the open location's UI state, or `None` while browsing.
+    pub fn session(&self, model: &Model) -> Option<Session> {
+        let InteractionMode::InspectingLocation { state } = &model.interaction_mode else {
+            return None;
+        };
+        Some(Session {
+            location_id: state.location.tag.id.clone(),
+            view: match state.view {
+                InspectingLocationView::SummaryScreen => SessionView::Summary,
+                InspectingLocationView::InspectScreen => SessionView::Inspect,
+            },
+            active_layer: Some(state.active_layer.clone()),
+            selected_region: self.inspect_screen.selected_region_id(&state.location),
+            browse_map: Some(self.location_select_screen.map.viewport()),
+            summary_map: Some(self.summary_screen.map.viewport()),
+            inspect_map: Some(self.inspect_screen.map.viewport()),
+        })
+    }
+
+    /// Apply the view-owned half of a session (map viewports, selection).
+    /// Runs after the screen's `Activated` handling so it wins over any fit.
+    pub fn restore(&mut self, session: &Session, location: &Location) {
+        if let Some(v) = &session.browse_map {
+            self.location_select_screen.map.set_viewport(v);
+        }
+        if let Some(v) = &session.summary_map {
+            self.summary_screen.map.set_viewport(v);
+        }
+        if let Some(v) = &session.inspect_map {
+            self.inspect_screen.map.set_viewport(v);
+        }
+        if let Some(region_id) = &session.selected_region {
+            self.inspect_screen.select_region_by_id(location, region_id);
+        }
+    }
+
+    /// Export the open location's map as last rendered.
+    pub fn map_export(&self, model: &Model, format: MapFormat) -> Result<Vec<u8>, Box<dyn Error>> {
+        let InteractionMode::InspectingLocation { state } = &model.interaction_mode else {
+            return Err("map export needs an open location".into());
+        };
+        let starred = model.saved_state.is_starred(&state.location.tag.id);
+        match state.view {
+            InspectingLocationView::SummaryScreen => {
+                let ctx = SummaryScreenCtx {
+                    location: &state.location,
+                    starred,
+                    clipboard: model.config.ui.clipboard,
+                    theme: model.config.theme(),
+                };
+                export::encode_map(self.summary_screen.map_svg(&ctx)?, format)
+            }
+            InspectingLocationView::InspectScreen => {
+                let ctx = InspectScreenCtx {
+                    location: &state.location,
+                    layers: &state.layers,
+                    active_layer: &state.active_layer,
+                    configured_layers: &model.config.layers,
+                    starred,
+                    clipboard: model.config.ui.clipboard,
+                    theme: model.config.theme(),
+                };
+                export::encode_map(self.inspect_screen.map_svg(&ctx)?, format)
+            }
+        }
+    }
 }
 
 impl Component for View {
@@ -50,7 +123,9 @@ impl Component for View {
                 InspectingLocationView::SummaryScreen => {
                     let ctx = SummaryScreenCtx {
                         location,
-                        err: &ctx.err,
+                        starred: ctx.saved_state.is_starred(&location.tag.id),
+                        clipboard: ctx.config.ui.clipboard,
+                        theme: ctx.config.theme(),
                     };
                     self.summary_screen.update(msg, ctx, db)
                 }
@@ -60,7 +135,9 @@ impl Component for View {
                         layers,
                         active_layer,
                         configured_layers: &ctx.config.layers,
-                        err: &ctx.err,
+                        starred: ctx.saved_state.is_starred(&location.tag.id),
+                        clipboard: ctx.config.ui.clipboard,
+                        theme: ctx.config.theme(),
                     };
                     self.inspect_screen.update(msg, ctx, db)
                 }
@@ -84,7 +161,9 @@ impl Component for View {
                 InspectingLocationView::SummaryScreen => {
                     let screen_ctx = SummaryScreenCtx {
                         location,
-                        err: &ctx.err,
+                        starred: ctx.saved_state.is_starred(&location.tag.id),
+                        clipboard: ctx.config.ui.clipboard,
+                        theme: ctx.config.theme(),
                     };
                     self.summary_screen.render(frame, area, screen_ctx)
                 }
@@ -94,11 +173,20 @@ impl Component for View {
                         layers,
                         active_layer,
                         configured_layers: &ctx.config.layers,
-                        err: &ctx.err,
+                        starred: ctx.saved_state.is_starred(&location.tag.id),
+                        clipboard: ctx.config.ui.clipboard,
+                        theme: ctx.config.theme(),
                     };
                     self.inspect_screen.render(frame, area, screen_ctx)
                 }
             },
         }
+        let theme = ctx.config.theme();
+        if let Some(toast) = &ctx.toast {
+            render_toast(frame, area, toast, &theme);
+        }
+        if let Some(err) = &ctx.err {
+            render_error_popup(frame, area, err, &theme);
+        }
     }
 }
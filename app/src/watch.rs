@@ -0,0 +1,84 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{config::Config, message::Message};
+
+/// Pipelines rewrite several files in a row; wait this long after the last
+/// change before reloading once for the whole burst.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches the dataset directory and the config files, sending
+/// `Message::Reload` after each burst of changes. Stops when dropped.
+pub struct FileWatcher {
+    _watcher: RecommendedWatcher,
+    data_root: PathBuf,
+    config_files: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+    pub fn spawn(
+        data_root: &Path,
+        config_files: &[PathBuf],
+        tx: mpsc::Sender<Message>,
+    ) -> notify::Result<Self> {
+        let (event_tx, event_rx) = mpsc::channel::<()>();
+        // Event paths are reported relative to what was watched, so watch
+        // canonical paths and compare against those.
+        let root = data_root.canonicalize().map_err(notify::Error::io)?;
+        let configs = config_files
+            .iter()
+            .map(|p| p.canonicalize().map_err(notify::Error::io))
+            .collect::<notify::Result<Vec<_>>>()?;
+
+        let filter_root = root.clone();
+        let filter_configs = configs.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            // Config directories are watched whole (editors save by replacing
+            // the file), so ignore their other files.
+            let relevant = event
+                .paths
+                .iter()
+                .any(|p| p.starts_with(&filter_root) || filter_configs.contains(p));
+            if relevant {
+                let _ = event_tx.send(());
+            }
+        })?;
+
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+        for dir in configs.iter().filter_map(|c| c.parent()) {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+
+        thread::spawn(move || {
+            while event_rx.recv().is_ok() {
+                while event_rx.recv_timeout(DEBOUNCE).is_ok() {}
+                if tx.send(Message::Reload).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Self {
+            _watcher: watcher,
+            data_root: data_root.to_path_buf(),
+            config_files: config_files.to_vec(),
+        })
+    }
+
+    /// Whether this watcher covers the files `config` was loaded from.
+    pub fn watches(&self, config: &Config) -> bool {
+        self.data_root == config.data.root_dir && self.config_files == config.source.files
+    }
+}
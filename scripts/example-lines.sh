@@ -0,0 +1,30 @@
+#!/bin/bash
+# Example lines layer: a spoke from the location's centre to every region.
+#
+# Demonstrates a layer with `kind = "lines"`: instead of a value per region it
+# prints a list of line collections, in the same shape as a polygon file's
+# `lines` list. Coordinates are in the same local metres as the rings, so the
+# location's centre is [0, 0].
+#
+# LOCTUI_LOCATION_ID and LOCTUI_DATA_ROOT are provided as env vars by the app.
+set -euo pipefail
+
+POLY_FILE="${LOCTUI_DATA_ROOT}/polygons/${LOCTUI_LOCATION_ID}.json"
+
+ids_json="$(cat)"
+
+jq -c --argjson ids "$ids_json" '
+  def centroid:
+    .[:-1] as $pts
+    | ($pts | length) as $n
+    | [(($pts | map(.[0]) | add) / $n), (($pts | map(.[1]) | add) / $n)];
+
+  [{
+    id: "region-spokes",
+    name: "Region spokes",
+    style: {dash: "dotted"},
+    lines: [.regions[]
+      | select(.id as $id | $ids | index($id))
+      | [[0, 0], (.ring | centroid)]]
+  }]
+' "$POLY_FILE"
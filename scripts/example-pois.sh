@@ -0,0 +1,34 @@
+#!/bin/bash
+# Example POI layer: a marker at the middle of every region.
+#
+# Demonstrates a layer with `kind = "pois"`: instead of a value per region it
+# prints a list of POI collections, in the same shape as a polygon file's
+# `pois` list. Coordinates are in the same local metres as the rings.
+#
+# LOCTUI_LOCATION_ID and LOCTUI_DATA_ROOT are provided as env vars by the app.
+set -euo pipefail
+
+POLY_FILE="${LOCTUI_DATA_ROOT}/polygons/${LOCTUI_LOCATION_ID}.json"
+
+ids_json="$(cat)"
+
+jq -c --argjson ids "$ids_json" '
+  def centroid:
+    .[:-1] as $pts
+    | ($pts | length) as $n
+    | [(($pts | map(.[0]) | add) / $n), (($pts | map(.[1]) | add) / $n)];
+
+  [{
+    id: "region-centres",
+    name: "Region centres",
+    category: "centre",
+    pois: [.regions[]
+      | select(.id as $id | $ids | index($id))
+      | {
+          id: (.id + "-centre"),
+          name: .name,
+          coord: (.ring | centroid),
+          attributes: {region: .id, vertices: (.ring | length)}
+        }]
+  }]
+' "$POLY_FILE"